@@ -0,0 +1,40 @@
+use axum::{
+    Json,
+    extract::multipart::MultipartError,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// 오류가 발생했을 때 반환하는 구조체
+pub struct AppError {
+    code: StatusCode,
+    message: String,
+}
+
+impl AppError {
+    pub fn new(code: StatusCode, message: impl Into<String>) -> Self {
+        AppError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// 원인은 로그로 남기고 클라이언트에는 알리지 않는 저장소 오류
+    pub fn storage(err: std::io::Error) -> Self {
+        tracing::error!("저장소 오류: {err}");
+        AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Storage error")
+    }
+}
+
+impl From<MultipartError> for AppError {
+    fn from(err: MultipartError) -> Self {
+        AppError::new(err.status(), err.body_text())
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        (self.code, Json(json!(self.message))).into_response()
+    }
+}
@@ -0,0 +1,40 @@
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, FromRef},
+    routing::{head, post},
+};
+
+use crate::{
+    resumable::{
+        ResumableUploads, append_upload, complete_upload, create_upload, delete_upload,
+        upload_offset,
+    },
+    upload::{UploadConfig, upload},
+};
+
+pub mod error;
+pub mod resumable;
+pub mod upload;
+
+#[derive(FromRef, Clone)]
+pub struct AppState {
+    pub config: UploadConfig,
+    pub resumable: ResumableUploads,
+}
+
+/// 서버 바이너리와 통합 테스트가 함께 쓰는 라우터를 만든다.
+pub fn router(state: AppState) -> Router {
+    // 크기 제한은 핸들러에서 필드별로 직접 검사한다.
+    Router::new()
+        .route("/", post(upload))
+        .route("/uploads", post(create_upload))
+        .route(
+            "/uploads/{id}",
+            head(upload_offset)
+                .patch(append_upload)
+                .delete(delete_upload),
+        )
+        .route("/uploads/{id}/complete", post(complete_upload))
+        .layer(DefaultBodyLimit::disable())
+        .with_state(state)
+}
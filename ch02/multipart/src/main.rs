@@ -1,20 +1,39 @@
-use axum::{Router, extract::Multipart, routing::post};
+use std::time::Duration;
 
-async fn uplaod(mut body: Multipart) -> String {
-    if let Ok(Some(field)) = body.next_field().await {
-        let name = field.name().unwrap().to_string();
-        let data = field.bytes().await.unwrap();
-        format!("{} : {} bytes", name, data.len())
-    } else {
-        "No field found in multipart data".to_string()
-    }
-}
+use multipart::{AppState, resumable::ResumableUploads, router, upload::UploadConfig};
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() {
-    let app = Router::new().route("/", post(uplaod));
+    // RUST_LOG이 없으면 필드마다 남기는 진행 상황 로그까지 보여준다.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("info,multipart=debug")),
+        )
+        .init();
+
+    let config = UploadConfig {
+        dir: "uploads".into(),
+        max_field_bytes: 10 * 1024 * 1024,
+        max_total_bytes: 50 * 1024 * 1024,
+        max_text_bytes: 64 * 1024,
+    };
+    tokio::fs::create_dir_all(&config.dir).await.unwrap();
+
+    // 하루가 지난 세션은 지우고, 한 시간마다 만료된 세션을 찾는다.
+    let resumable = ResumableUploads::new(
+        "uploads/partial",
+        "uploads",
+        10 * 1024 * 1024 * 1024,
+        Duration::from_secs(24 * 60 * 60),
+    );
+    resumable.init().await.unwrap();
+    resumable.spawn_sweeper(Duration::from_secs(60 * 60));
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await.unwrap();
 
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, router(AppState { config, resumable }))
+        .await
+        .unwrap();
 }
@@ -0,0 +1,449 @@
+//! tus 방식의 이어 올리기
+//!
+//! 1. `POST /uploads`에 `Upload-Length` 헤더로 전체 크기를 알려서 세션을 만든다.
+//! 2. `PATCH /uploads/{id}`에 `Upload-Offset` 헤더와 함께 이어서 보낼 데이터를 보낸다.
+//! 3. 연결이 끊기면 `HEAD /uploads/{id}`로 서버가 받은 위치를 확인하고 2부터 다시 한다.
+//! 4. 다 보냈으면 `POST /uploads/{id}/complete`에 SHA-256을 보내서 검증하고 마무리한다.
+//! 5. 그만 올리려면 `DELETE /uploads/{id}`로 세션을 지운다.
+//!
+//! 세션 정보와 받은 데이터는 디스크에 저장하므로 서버를 다시 시작해도 이어서 올릴 수 있다.
+//! 만든 지 `max_age`가 지난 세션은 시작할 때와 주기적인 정리에서 지운다.
+
+use std::{
+    collections::HashSet,
+    path::{Path as FsPath, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderName, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+const UPLOAD_LENGTH: HeaderName = HeaderName::from_static("upload-length");
+const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+
+/// 이어 올리기 세션 저장소
+#[derive(Clone)]
+pub struct ResumableUploads {
+    /// 올리는 중인 파일과 세션 정보를 저장할 디렉터리
+    partial_dir: PathBuf,
+    /// 완료된 파일을 옮길 디렉터리
+    complete_dir: PathBuf,
+    /// 파일 하나의 최대 크기
+    max_length: u64,
+    /// 세션을 만든 뒤 이어 올릴 수 있는 기간
+    max_age: Duration,
+    /// 지금 데이터를 받고 있는 세션. 같은 세션에 동시에 쓰지 못하게 한다.
+    active: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+/// 디스크에 저장하는 세션 정보
+/// 받은 위치는 따로 저장하지 않고 `.part` 파일의 크기를 그대로 쓴다.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    length: u64,
+    /// 세션을 만든 시각(유닉스 초). 이 값이 없는 예전 세션은 세션 파일의 수정 시각을 쓴다.
+    #[serde(default)]
+    created_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct Complete {
+    sha256: String,
+}
+
+#[derive(Serialize)]
+pub struct Completed {
+    id: Uuid,
+    size: u64,
+    sha256: String,
+    stored_as: String,
+}
+
+/// 세션에 데이터를 쓰는 동안 잡고 있는 잠금. drop되면 풀린다.
+struct ActiveGuard {
+    active: Arc<Mutex<HashSet<Uuid>>>,
+    id: Uuid,
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.active.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl ResumableUploads {
+    pub fn new(
+        partial_dir: impl Into<PathBuf>,
+        complete_dir: impl Into<PathBuf>,
+        max_length: u64,
+        max_age: Duration,
+    ) -> Self {
+        ResumableUploads {
+            partial_dir: partial_dir.into(),
+            complete_dir: complete_dir.into(),
+            max_length,
+            max_age,
+            active: Arc::default(),
+        }
+    }
+
+    /// 디렉터리를 만들고 서버가 꺼져 있는 동안 만료된 세션을 지운다.
+    pub async fn init(&self) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.partial_dir).await?;
+        tokio::fs::create_dir_all(&self.complete_dir).await?;
+        self.remove_expired().await?;
+        Ok(())
+    }
+
+    /// `interval`마다 만료된 세션을 지우는 작업을 띄운다.
+    pub fn spawn_sweeper(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let uploads = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // 첫 틱은 바로 지나가고, 시작할 때의 정리는 `init`이 한다.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(err) = uploads.remove_expired().await {
+                    tracing::error!("만료된 업로드 세션 정리 실패: {err}");
+                }
+            }
+        })
+    }
+
+    /// 만든 지 `max_age`가 지난 세션과 받은 데이터를 지우고 지운 세션 수를 반환한다.
+    /// 지금 데이터를 받고 있는 세션은 건너뛴다.
+    pub async fn remove_expired(&self) -> std::io::Result<usize> {
+        let mut removed = 0;
+        let mut entries = tokio::fs::read_dir(&self.partial_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| Uuid::parse_str(stem).ok())
+            else {
+                continue;
+            };
+            let Ok(_guard) = self.lock(id) else {
+                continue;
+            };
+            if !self.is_expired(&path).await? {
+                continue;
+            }
+
+            remove_if_exists(&self.data_path(id)).await?;
+            remove_if_exists(&path).await?;
+            tracing::info!("{id}: 만료된 업로드 세션 삭제");
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// 세션 파일의 생성 시각으로 만료되었는지 확인한다. 읽을 수 없는 세션 파일도 만료된 것으로 본다.
+    async fn is_expired(&self, session_path: &FsPath) -> std::io::Result<bool> {
+        let created_at = match tokio::fs::read(session_path).await {
+            Ok(session) => serde_json::from_slice::<Session>(&session)
+                .ok()
+                .map(|session| session.created_at),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        let created_at = match created_at {
+            Some(Some(secs)) => UNIX_EPOCH + Duration::from_secs(secs),
+            Some(None) => tokio::fs::metadata(session_path).await?.modified()?,
+            None => return Ok(true),
+        };
+
+        Ok(SystemTime::now()
+            .duration_since(created_at)
+            .is_ok_and(|age| age > self.max_age))
+    }
+
+    fn session_path(&self, id: Uuid) -> PathBuf {
+        self.partial_dir.join(format!("{id}.json"))
+    }
+
+    fn data_path(&self, id: Uuid) -> PathBuf {
+        self.partial_dir.join(format!("{id}.part"))
+    }
+
+    async fn load(&self, id: Uuid) -> Result<(Session, u64), AppError> {
+        let session = match tokio::fs::read(self.session_path(id)).await {
+            Ok(session) => session,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AppError::new(StatusCode::NOT_FOUND, "Upload not found"));
+            }
+            Err(err) => return Err(AppError::storage(err)),
+        };
+        let session = serde_json::from_slice::<Session>(&session).map_err(|err| {
+            AppError::storage(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        })?;
+        let offset = tokio::fs::metadata(self.data_path(id))
+            .await
+            .map_err(AppError::storage)?
+            .len();
+
+        Ok((session, offset))
+    }
+
+    fn lock(&self, id: Uuid) -> Result<ActiveGuard, AppError> {
+        if !self.active.lock().unwrap().insert(id) {
+            return Err(AppError::new(
+                StatusCode::LOCKED,
+                "Upload is being written by another request",
+            ));
+        }
+
+        Ok(ActiveGuard {
+            active: self.active.clone(),
+            id,
+        })
+    }
+}
+
+async fn remove_if_exists(path: &FsPath) -> std::io::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(id).map_err(|_| AppError::new(StatusCode::NOT_FOUND, "Upload not found"))
+}
+
+fn parse_u64_header(headers: &HeaderMap, name: &HeaderName) -> Result<u64, AppError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::BAD_REQUEST,
+                format!("{name} header must be a non-negative integer"),
+            )
+        })
+}
+
+/// "POST /uploads" 핸들러
+/// 새 세션을 만들고 `Location` 헤더로 세션 주소를 알려준다.
+pub async fn create_upload(
+    State(uploads): State<ResumableUploads>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let length = parse_u64_header(&headers, &UPLOAD_LENGTH)?;
+    if length > uploads.max_length {
+        return Err(AppError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Upload must be at most {} bytes", uploads.max_length),
+        ));
+    }
+
+    let id = Uuid::new_v4();
+    let data_path = uploads.data_path(id);
+    File::create(&data_path).await.map_err(AppError::storage)?;
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .ok();
+    let session =
+        serde_json::to_vec(&Session { length, created_at }).expect("Session is serializable");
+    if let Err(err) = tokio::fs::write(uploads.session_path(id), session).await {
+        // 세션 정보가 없는 데이터 파일은 아무도 이어 쓰거나 지울 수 없다.
+        let _ = tokio::fs::remove_file(&data_path).await;
+        return Err(AppError::storage(err));
+    }
+
+    tracing::info!("{id}: 업로드 세션 생성 ({length} bytes)");
+
+    Ok((
+        StatusCode::CREATED,
+        [
+            (header::LOCATION, format!("/uploads/{id}")),
+            (UPLOAD_OFFSET, "0".to_string()),
+        ],
+    )
+        .into_response())
+}
+
+/// "HEAD /uploads/{id}" 핸들러
+/// 지금까지 받은 위치를 알려준다.
+pub async fn upload_offset(
+    State(uploads): State<ResumableUploads>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    let id = parse_id(&id)?;
+    let (session, offset) = uploads.load(id).await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (UPLOAD_OFFSET, offset.to_string()),
+            (UPLOAD_LENGTH, session.length.to_string()),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+    )
+        .into_response())
+}
+
+/// "PATCH /uploads/{id}" 핸들러
+/// `Upload-Offset`이 서버가 받은 위치와 같을 때만 이어서 쓴다.
+pub async fn append_upload(
+    State(uploads): State<ResumableUploads>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, AppError> {
+    let id = parse_id(&id)?;
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    if content_type != Some("application/offset+octet-stream") {
+        return Err(AppError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Content-Type must be application/offset+octet-stream",
+        ));
+    }
+    let requested_offset = parse_u64_header(&headers, &UPLOAD_OFFSET)?;
+
+    let _guard = uploads.lock(id)?;
+    let (session, mut offset) = uploads.load(id).await?;
+    if requested_offset != offset {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            format!("Upload-Offset must be {offset}"),
+        ));
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(uploads.data_path(id))
+        .await
+        .map_err(AppError::storage)?;
+    let mut stream = body.into_data_stream();
+
+    // 연결이 끊겨도 그때까지 쓴 데이터는 남으므로 다음 요청에서 이어서 받을 수 있다.
+    let result = async {
+        while let Some(chunk) = stream.next().await {
+            let chunk =
+                chunk.map_err(|_| AppError::new(StatusCode::BAD_REQUEST, "Failed to read body"))?;
+            if offset + chunk.len() as u64 > session.length {
+                return Err(AppError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "Data exceeds Upload-Length",
+                ));
+            }
+            file.write_all(&chunk).await.map_err(AppError::storage)?;
+            offset += chunk.len() as u64;
+        }
+        Ok(())
+    }
+    .await;
+
+    file.flush().await.map_err(AppError::storage)?;
+    result?;
+
+    tracing::info!("{id}: {offset}/{} bytes 수신", session.length);
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(UPLOAD_OFFSET, offset.to_string())],
+    )
+        .into_response())
+}
+
+/// "POST /uploads/{id}/complete" 핸들러
+/// 모든 데이터를 받았는지와 SHA-256이 맞는지 확인하고 완료된 파일로 옮긴다.
+pub async fn complete_upload(
+    State(uploads): State<ResumableUploads>,
+    Path(id): Path<String>,
+    Json(complete): Json<Complete>,
+) -> Result<Json<Completed>, AppError> {
+    let id = parse_id(&id)?;
+    let _guard = uploads.lock(id)?;
+    let (session, offset) = uploads.load(id).await?;
+    if offset != session.length {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            format!("Upload is not complete: {offset}/{} bytes", session.length),
+        ));
+    }
+
+    let data_path = uploads.data_path(id);
+    let mut file = File::open(&data_path).await.map_err(AppError::storage)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).await.map_err(AppError::storage)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    let sha256 = hex::encode(hasher.finalize());
+
+    if !sha256.eq_ignore_ascii_case(complete.sha256.trim()) {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Checksum mismatch: received data has SHA-256 {sha256}"),
+        ));
+    }
+
+    let stored_as = id.to_string();
+    tokio::fs::rename(&data_path, uploads.complete_dir.join(&stored_as))
+        .await
+        .map_err(AppError::storage)?;
+    tokio::fs::remove_file(uploads.session_path(id))
+        .await
+        .map_err(AppError::storage)?;
+
+    tracing::info!("{id}: 업로드 완료");
+
+    Ok(Json(Completed {
+        id,
+        size: session.length,
+        sha256,
+        stored_as,
+    }))
+}
+
+/// "DELETE /uploads/{id}" 핸들러
+/// 세션과 지금까지 받은 데이터를 지운다.
+pub async fn delete_upload(
+    State(uploads): State<ResumableUploads>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let id = parse_id(&id)?;
+    let _guard = uploads.lock(id)?;
+    uploads.load(id).await?;
+
+    tokio::fs::remove_file(uploads.session_path(id))
+        .await
+        .map_err(AppError::storage)?;
+    tokio::fs::remove_file(uploads.data_path(id))
+        .await
+        .map_err(AppError::storage)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,192 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use axum::{
+    Json,
+    extract::{Multipart, State, multipart::Field},
+    http::StatusCode,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::{fs::File, io::AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// 진행 상황을 로그로 남기는 간격 (1MB)
+const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// 업로드 설정
+#[derive(Clone)]
+pub struct UploadConfig {
+    /// 파일을 저장할 디렉터리
+    pub dir: PathBuf,
+    /// 필드 하나의 최대 크기
+    pub max_field_bytes: u64,
+    /// 요청 전체의 최대 크기
+    pub max_total_bytes: u64,
+    /// 텍스트 필드 하나의 최대 크기. 텍스트는 메모리에 올리므로 작게 잡는다.
+    pub max_text_bytes: u64,
+}
+
+/// 저장된 파일 정보
+#[derive(Serialize)]
+pub struct FileEntry {
+    field: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size: u64,
+    sha256: String,
+    stored_as: String,
+}
+
+/// 요청으로 받은 모든 필드의 목록
+#[derive(Serialize, Default)]
+pub struct Manifest {
+    fields: BTreeMap<String, Vec<String>>,
+    files: Vec<FileEntry>,
+    total_bytes: u64,
+}
+
+/// 요청 하나에서 받은 바이트 수를 세고 제한을 검사한다.
+struct Limits<'a> {
+    config: &'a UploadConfig,
+    total: u64,
+}
+
+impl Limits<'_> {
+    /// 필드에 `len` 바이트가 더 들어왔을 때 제한을 넘는지 검사한다.
+    fn add(&mut self, field_size: &mut u64, len: usize, field_limit: u64) -> Result<(), AppError> {
+        *field_size += len as u64;
+        self.total += len as u64;
+
+        if *field_size > field_limit {
+            return Err(AppError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Each field must be at most {field_limit} bytes"),
+            ));
+        }
+        if self.total > self.config.max_total_bytes {
+            return Err(AppError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Request must be at most {} bytes",
+                    self.config.max_total_bytes
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// 파일 필드를 청크 단위로 디스크에 쓰면서 SHA-256을 계산한다.
+async fn save_file_field(
+    field: &mut Field<'_>,
+    name: &str,
+    file: &mut File,
+    limits: &mut Limits<'_>,
+) -> Result<(u64, String), AppError> {
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+
+    while let Some(chunk) = field.chunk().await? {
+        let before = size;
+        limits.add(&mut size, chunk.len(), limits.config.max_field_bytes)?;
+        hasher.update(&chunk);
+        file.write_all(&chunk).await.map_err(AppError::storage)?;
+
+        if before / PROGRESS_INTERVAL != size / PROGRESS_INTERVAL {
+            tracing::debug!("{name}: {size} bytes 수신");
+        }
+    }
+
+    file.flush().await.map_err(AppError::storage)?;
+
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// 텍스트 필드를 읽는다.
+async fn read_text_field(
+    field: &mut Field<'_>,
+    limits: &mut Limits<'_>,
+) -> Result<String, AppError> {
+    let mut value = Vec::new();
+    let mut size = 0;
+
+    while let Some(chunk) = field.chunk().await? {
+        limits.add(&mut size, chunk.len(), limits.config.max_text_bytes)?;
+        value.extend_from_slice(&chunk);
+    }
+
+    String::from_utf8(value)
+        .map_err(|_| AppError::new(StatusCode::BAD_REQUEST, "Text field must be valid UTF-8"))
+}
+
+/// 모든 필드를 처리해서 목록을 만든다.
+/// 저장한 파일의 경로는 `stored`에 기록해서 실패했을 때 지울 수 있게 한다.
+async fn receive(
+    config: &UploadConfig,
+    multipart: &mut Multipart,
+    stored: &mut Vec<PathBuf>,
+) -> Result<Manifest, AppError> {
+    let mut manifest = Manifest::default();
+    let mut limits = Limits { config, total: 0 };
+
+    while let Some(mut field) = multipart.next_field().await? {
+        let Some(name) = field.name().map(str::to_string) else {
+            return Err(AppError::new(
+                StatusCode::BAD_REQUEST,
+                "Every field must have a name",
+            ));
+        };
+
+        // 파일 이름이 없는 필드는 일반 폼 값으로 취급한다.
+        if field.file_name().is_none() {
+            let value = read_text_field(&mut field, &mut limits).await?;
+            manifest.fields.entry(name).or_default().push(value);
+            continue;
+        }
+
+        // 클라이언트가 보낸 파일 이름은 경로로 쓰지 않는다.
+        let stored_as = Uuid::new_v4().to_string();
+        let path = config.dir.join(&stored_as);
+        let mut file = File::create(&path).await.map_err(AppError::storage)?;
+        stored.push(path);
+
+        let (size, sha256) = save_file_field(&mut field, &name, &mut file, &mut limits).await?;
+        tracing::info!("{name}: {size} bytes 저장 완료");
+
+        manifest.files.push(FileEntry {
+            file_name: field.file_name().map(str::to_string),
+            content_type: field.content_type().map(str::to_string),
+            field: name,
+            size,
+            sha256,
+            stored_as,
+        });
+    }
+
+    manifest.total_bytes = limits.total;
+
+    Ok(manifest)
+}
+
+/// "POST /" 핸들러
+/// 모든 필드를 받아서 파일은 디스크에 저장하고, 받은 내용의 목록을 반환한다.
+/// 하나라도 실패하면 이번 요청에서 저장한 파일을 모두 지운다.
+pub async fn upload(
+    State(config): State<UploadConfig>,
+    mut multipart: Multipart,
+) -> Result<Json<Manifest>, AppError> {
+    let mut stored = Vec::new();
+
+    match receive(&config, &mut multipart, &mut stored).await {
+        Ok(manifest) => Ok(Json(manifest)),
+        Err(err) => {
+            for path in stored {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! 통합 테스트 공용 도구
+//!
+//! 테스트마다 임시 디렉터리에 저장하는 라우터를 만들어서 `oneshot`으로 요청을 보낸다.
+//! 임시 디렉터리는 `TestApp`을 버릴 때 지워진다.
+
+// 테스트 파일마다 따로 컴파일되므로 쓰지 않는 도우미가 생긴다.
+#![allow(dead_code)]
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderMap, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use multipart::{AppState, resumable::ResumableUploads, router, upload::UploadConfig};
+use serde_json::Value;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+pub struct TestApp {
+    pub router: Router,
+    pub resumable: ResumableUploads,
+    dir: TempDir,
+}
+
+/// 응답의 상태 코드, 헤더, JSON 본문. 본문이 비어 있으면 `Value::Null`이다.
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Value,
+}
+
+impl TestResponse {
+    pub fn header(&self, name: &str) -> &str {
+        self.headers[name].to_str().unwrap()
+    }
+}
+
+impl TestApp {
+    /// 작은 크기 제한을 건 라우터를 만든다.
+    /// 필드 하나는 1024 bytes, 요청 전체는 2048 bytes, 텍스트 필드는 64 bytes, 이어 올리기는 4096 bytes까지 받는다.
+    /// 이어 올리기 세션은 한 시간 동안 유지한다.
+    pub async fn spawn() -> Self {
+        let dir = TempDir::new().unwrap();
+        let config = UploadConfig {
+            dir: dir.path().join("files"),
+            max_field_bytes: 1024,
+            max_total_bytes: 2048,
+            max_text_bytes: 64,
+        };
+        tokio::fs::create_dir_all(&config.dir).await.unwrap();
+        let resumable = ResumableUploads::new(
+            dir.path().join("partial"),
+            dir.path().join("complete"),
+            4096,
+            Duration::from_secs(60 * 60),
+        );
+        resumable.init().await.unwrap();
+
+        TestApp {
+            router: router(AppState {
+                config,
+                resumable: resumable.clone(),
+            }),
+            resumable,
+            dir,
+        }
+    }
+
+    /// 저장소 안의 경로
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    /// 디렉터리에 있는 파일 이름 목록
+    pub fn files(&self, name: &str) -> Vec<String> {
+        list(&self.path(name))
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+        };
+
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+fn list(dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().unwrap().is_file())
+        .map(|entry| entry.file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
@@ -0,0 +1,213 @@
+//! 이어 올리기(`/uploads`) 통합 테스트
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use common::{TestApp, TestResponse};
+
+async fn create(app: &TestApp, length: u64) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/uploads")
+        .header("upload-length", length)
+        .body(Body::empty())
+        .unwrap();
+    app.send(request).await
+}
+
+async fn append(app: &TestApp, location: &str, offset: u64, data: &[u8]) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::PATCH)
+        .uri(location)
+        .header("content-type", "application/offset+octet-stream")
+        .header("upload-offset", offset)
+        .body(Body::from(data.to_vec()))
+        .unwrap();
+    app.send(request).await
+}
+
+async fn offset(app: &TestApp, location: &str) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::HEAD)
+        .uri(location)
+        .body(Body::empty())
+        .unwrap();
+    app.send(request).await
+}
+
+async fn complete(app: &TestApp, location: &str, sha256: &str) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("{location}/complete"))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "sha256": sha256 }).to_string()))
+        .unwrap();
+    app.send(request).await
+}
+
+async fn delete(app: &TestApp, location: &str) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri(location)
+        .body(Body::empty())
+        .unwrap();
+    app.send(request).await
+}
+
+/// 세션을 만들고 `Location`을 반환한다.
+async fn start(app: &TestApp, length: u64) -> String {
+    let response = create(app, length).await;
+    assert_eq!(response.status, StatusCode::CREATED);
+    assert_eq!(response.header("upload-offset"), "0");
+    response.header("location").to_string()
+}
+
+#[tokio::test]
+async fn upload_resumes_and_completes() {
+    let app = TestApp::spawn().await;
+    let data = b"hello, resumable world";
+    let location = start(&app, data.len() as u64).await;
+
+    let response = append(&app, &location, 0, &data[..5]).await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+    assert_eq!(response.header("upload-offset"), "5");
+
+    // 연결이 끊긴 뒤 받은 위치를 확인하고 이어서 보낸다.
+    let response = offset(&app, &location).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.header("upload-offset"), "5");
+    assert_eq!(response.header("upload-length"), data.len().to_string());
+
+    // 다 받기 전에는 완료할 수 없다.
+    let response = complete(&app, &location, "").await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+
+    let response = append(&app, &location, 5, &data[5..]).await;
+    assert_eq!(response.header("upload-offset"), data.len().to_string());
+
+    let response = complete(&app, &location, "0000").await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let sha256 = hex::encode(Sha256::digest(data));
+    let response = complete(&app, &location, &sha256.to_uppercase()).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body["size"], data.len());
+    assert_eq!(response.body["sha256"], sha256);
+
+    let stored_as = response.body["stored_as"].as_str().unwrap();
+    assert_eq!(app.files("complete"), [stored_as]);
+    assert_eq!(
+        std::fs::read(app.path("complete").join(stored_as)).unwrap(),
+        data
+    );
+    assert!(app.files("partial").is_empty());
+
+    // 완료된 세션은 더 이상 없다.
+    assert_eq!(offset(&app, &location).await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn wrong_offset_is_rejected() {
+    let app = TestApp::spawn().await;
+    let location = start(&app, 10).await;
+    append(&app, &location, 0, b"abc").await;
+
+    for wrong in [0, 2, 4] {
+        let response = append(&app, &location, wrong, b"def").await;
+        assert_eq!(response.status, StatusCode::CONFLICT, "{wrong}");
+        assert_eq!(response.body, "Upload-Offset must be 3");
+    }
+    assert_eq!(offset(&app, &location).await.header("upload-offset"), "3");
+}
+
+#[tokio::test]
+async fn data_past_upload_length_is_rejected() {
+    let app = TestApp::spawn().await;
+    let location = start(&app, 4).await;
+
+    let response = append(&app, &location, 0, b"abcdef").await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    // 넘친 청크는 쓰지 않는다.
+    assert_eq!(offset(&app, &location).await.header("upload-offset"), "0");
+
+    let response = create(&app, 4097).await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn missing_session_is_404() {
+    let app = TestApp::spawn().await;
+    // 만들지 않은 세션과 세션 id가 아닌 경로
+    for location in [
+        "/uploads/00000000-0000-4000-8000-000000000000",
+        "/uploads/not-a-uuid",
+    ] {
+        assert_eq!(offset(&app, location).await.status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            append(&app, location, 0, b"abc").await.status,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            complete(&app, location, "").await.status,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(delete(&app, location).await.status, StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn delete_removes_session_and_data() {
+    let app = TestApp::spawn().await;
+    let location = start(&app, 10).await;
+    append(&app, &location, 0, b"abc").await;
+    assert_eq!(app.files("partial").len(), 2);
+
+    assert_eq!(delete(&app, &location).await.status, StatusCode::NO_CONTENT);
+    assert!(app.files("partial").is_empty());
+    assert_eq!(offset(&app, &location).await.status, StatusCode::NOT_FOUND);
+}
+
+/// 세션 파일의 `created_at`을 바꾼다. None이면 예전 형식처럼 필드를 뺀다.
+fn set_created_at(app: &TestApp, location: &str, created_at: Option<u64>) {
+    let id = location.trim_start_matches("/uploads/");
+    let path = app.path("partial").join(format!("{id}.json"));
+    let mut session: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    match created_at {
+        Some(secs) => session["created_at"] = json!(secs),
+        None => {
+            session.as_object_mut().unwrap().remove("created_at");
+        }
+    }
+    std::fs::write(&path, session.to_string()).unwrap();
+}
+
+#[tokio::test]
+async fn expired_sessions_are_removed() {
+    let app = TestApp::spawn().await;
+    let expired = start(&app, 10).await;
+    append(&app, &expired, 0, b"abc").await;
+    let fresh = start(&app, 10).await;
+    let legacy = start(&app, 10).await;
+
+    // 두 시간 전에 만든 세션과 생성 시각이 없는 예전 세션
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    set_created_at(&app, &expired, Some(now - 2 * 60 * 60));
+    set_created_at(&app, &legacy, None);
+
+    assert_eq!(app.resumable.remove_expired().await.unwrap(), 1);
+    assert_eq!(offset(&app, &expired).await.status, StatusCode::NOT_FOUND);
+    assert_eq!(offset(&app, &fresh).await.status, StatusCode::OK);
+    // 예전 세션은 세션 파일의 수정 시각으로 판단한다.
+    assert_eq!(offset(&app, &legacy).await.status, StatusCode::OK);
+    assert_eq!(app.files("partial").len(), 4);
+}
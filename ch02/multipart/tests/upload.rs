@@ -0,0 +1,145 @@
+//! 멀티파트 업로드(`POST /`) 통합 테스트
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use common::{TestApp, TestResponse};
+
+const BOUNDARY: &str = "test-boundary";
+
+/// 필드 이름, 파일 이름, 내용으로 멀티파트 본문을 만든다. 파일 이름이 없으면 텍스트 필드다.
+fn multipart_body(fields: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, file_name, content) in fields {
+        let disposition = match file_name {
+            Some(file_name) => format!("form-data; name=\"{name}\"; filename=\"{file_name}\""),
+            None => format!("form-data; name=\"{name}\""),
+        };
+        body.extend_from_slice(
+            format!("--{BOUNDARY}\r\nContent-Disposition: {disposition}\r\n\r\n").as_bytes(),
+        );
+        body.extend_from_slice(content);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+    body
+}
+
+async fn upload(app: &TestApp, fields: &[(&str, Option<&str>, &[u8])]) -> TestResponse {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(multipart_body(fields)))
+        .unwrap();
+    app.send(request).await
+}
+
+#[tokio::test]
+async fn every_field_is_received() {
+    let app = TestApp::spawn().await;
+    let a = [b'a'; 1000];
+    let b = b"second file";
+
+    let response = upload(
+        &app,
+        &[
+            ("title", None, b"holiday"),
+            ("photo", Some("a.jpg"), &a),
+            ("tag", None, b"sea"),
+            ("photo", Some("b.txt"), b),
+            ("tag", None, b"sun"),
+        ],
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(
+        response.body["fields"],
+        json!({ "tag": ["sea", "sun"], "title": ["holiday"] })
+    );
+    assert_eq!(response.body["total_bytes"], 7 + 1000 + 3 + 11 + 3);
+
+    let files = response.body["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["file_name"], "a.jpg");
+    assert_eq!(files[0]["size"], 1000);
+    assert_eq!(files[0]["sha256"], hex::encode(Sha256::digest(a)));
+    assert_eq!(files[1]["sha256"], hex::encode(Sha256::digest(b)));
+
+    let stored_as = files[1]["stored_as"].as_str().unwrap();
+    assert_eq!(std::fs::read(app.path("files").join(stored_as)).unwrap(), b);
+    assert_eq!(app.files("files").len(), 2);
+}
+
+#[tokio::test]
+async fn oversized_field_is_413_and_keeps_no_files() {
+    let app = TestApp::spawn().await;
+
+    let response = upload(
+        &app,
+        &[
+            ("small", Some("a.bin"), &[0; 10]),
+            ("large", Some("b.bin"), &[0; 1025]),
+        ],
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(response.body, "Each field must be at most 1024 bytes");
+    assert!(app.files("files").is_empty());
+
+    let response = upload(&app, &[("note", None, &[b'x'; 65])]).await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(response.body, "Each field must be at most 64 bytes");
+}
+
+#[tokio::test]
+async fn oversized_request_is_413_and_keeps_no_files() {
+    let app = TestApp::spawn().await;
+
+    // 필드마다 제한 안이지만 합치면 2048 bytes를 넘는다.
+    let response = upload(
+        &app,
+        &[
+            ("a", Some("a.bin"), &[0; 1000]),
+            ("b", Some("b.bin"), &[0; 1000]),
+            ("c", Some("c.bin"), &[0; 100]),
+        ],
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(response.body, "Request must be at most 2048 bytes");
+    assert!(app.files("files").is_empty());
+}
+
+#[tokio::test]
+async fn non_multipart_requests_are_rejected() {
+    let app = TestApp::spawn().await;
+
+    for content_type in [Some("application/json"), Some("multipart/form-data"), None] {
+        let mut request = Request::builder().method(Method::POST).uri("/");
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let response = app.send(request.body(Body::from("{}")).unwrap()).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{content_type:?}");
+    }
+    assert!(app.files("files").is_empty());
+}
+
+#[tokio::test]
+async fn invalid_fields_are_rejected() {
+    let app = TestApp::spawn().await;
+
+    let response = upload(&app, &[("note", None, &[0xFF, 0xFE])]).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.body, "Text field must be valid UTF-8");
+}
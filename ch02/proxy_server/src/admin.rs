@@ -0,0 +1,100 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    breaker::{BreakerStatus, CircuitBreaker},
+    cache::{Cache, EntryInfo},
+    disk::DiskCache,
+};
+
+#[derive(Serialize)]
+pub struct CacheStatus {
+    total_bytes: usize,
+    max_bytes: usize,
+    entries: Vec<EntryInfo>,
+    /// 디스크 캐시를 쓰면 저장된 본문 크기의 합
+    disk_bytes: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct PurgeQuery {
+    key: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Purged {
+    purged: usize,
+}
+
+/// "GET /admin/cache" 핸들러
+/// 캐시에 저장된 항목을 최근에 사용한 순서대로 보여준다.
+pub async fn get_cache(
+    State(cache): State<Cache>,
+    State(disk): State<Option<DiskCache>>,
+) -> Json<CacheStatus> {
+    let disk_bytes = match &disk {
+        Some(disk) => Some(disk.bytes().await),
+        None => None,
+    };
+
+    Json(CacheStatus {
+        total_bytes: cache.bytes(),
+        max_bytes: cache.config().max_bytes,
+        entries: cache.entries(),
+        disk_bytes,
+    })
+}
+
+/// "DELETE /admin/cache" 핸들러
+/// key가 있으면 그 항목만, 없으면 모든 항목을 지운다.
+/// 디스크 캐시를 쓰면 디스크에서도 지우고, 두 캐시 중 더 많이 지운 개수를 반환한다.
+pub async fn purge_cache(
+    State(cache): State<Cache>,
+    State(disk): State<Option<DiskCache>>,
+    Query(query): Query<PurgeQuery>,
+) -> Result<(StatusCode, Json<Purged>), (StatusCode, String)> {
+    let storage_error = |err: std::io::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to purge disk cache: {err}"),
+        )
+    };
+
+    match query.key {
+        Some(key) => {
+            let in_memory = cache.remove(&key);
+            let on_disk = match &disk {
+                Some(disk) => disk.remove(&key).await.map_err(storage_error)?,
+                None => false,
+            };
+            if in_memory || on_disk {
+                Ok((StatusCode::OK, Json(Purged { purged: 1 })))
+            } else {
+                Ok((StatusCode::NOT_FOUND, Json(Purged { purged: 0 })))
+            }
+        }
+        None => {
+            let in_memory = cache.clear();
+            let on_disk = match &disk {
+                Some(disk) => disk.clear().await.map_err(storage_error)?,
+                None => 0,
+            };
+            Ok((
+                StatusCode::OK,
+                Json(Purged {
+                    purged: in_memory.max(on_disk),
+                }),
+            ))
+        }
+    }
+}
+
+/// "GET /admin/breaker" 핸들러
+/// 업스트림 서킷 브레이커의 상태를 보여준다.
+pub async fn get_breaker(State(breaker): State<CircuitBreaker>) -> Json<BreakerStatus> {
+    Json(breaker.status())
+}
@@ -0,0 +1,180 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// 서킷 브레이커 설정
+#[derive(Clone)]
+pub struct BreakerConfig {
+    /// 연속으로 이만큼 실패하면 서킷을 연다.
+    pub failure_threshold: u32,
+    /// 서킷을 연 뒤 다시 시험 요청을 보내기까지 기다리는 시간
+    pub cool_down: Duration,
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    /// 요청을 그대로 보낸다.
+    Closed,
+    /// `until`까지 요청을 보내지 않는다.
+    Open { until: Instant },
+    /// 시험 요청 하나만 보내고 그 결과로 닫을지 다시 열지 정한다.
+    HalfOpen,
+}
+
+struct Inner {
+    state: State,
+    /// 연속으로 실패한 횟수
+    failures: u32,
+    /// 서킷을 연 횟수
+    trips: u64,
+    /// 서킷이 열려 있어서 보내지 않은 요청 수
+    rejected: u64,
+}
+
+/// 업스트림이 계속 실패하면 한동안 요청을 보내지 않고 바로 실패시킨다.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    inner: Arc<Mutex<Inner>>,
+    config: BreakerConfig,
+}
+
+/// 업스트림 요청을 보내도 된다는 허가.
+/// 결과를 알리지 않고 drop되면 실패로 기록한다.
+pub struct Permit {
+    breaker: CircuitBreaker,
+    /// 허가를 받을 때까지 서킷을 연 횟수. 그 뒤에 서킷이 열렸으면 결과를 무시한다.
+    trips: u64,
+    done: bool,
+}
+
+/// 관리자 API와 메트릭에서 보여주는 서킷 브레이커 상태
+#[derive(Serialize)]
+pub struct BreakerStatus {
+    pub state: &'static str,
+    pub consecutive_failures: u32,
+    pub failure_threshold: u32,
+    /// 서킷이 열려 있으면 시험 요청을 보낼 수 있을 때까지 남은 시간
+    pub retry_in_secs: Option<u64>,
+    pub trips: u64,
+    pub rejected: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        CircuitBreaker {
+            inner: Arc::new(Mutex::new(Inner {
+                state: State::Closed,
+                failures: 0,
+                trips: 0,
+                rejected: 0,
+            })),
+            config,
+        }
+    }
+
+    /// 업스트림 요청을 보내도 되는지 확인한다.
+    /// 서킷이 열려 있으면 다시 시도해도 될 때까지 남은 시간을 반환한다.
+    pub fn acquire(&self) -> Result<Permit, Duration> {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+
+        match inner.state {
+            State::Closed => {}
+            State::Open { until } if until > now => {
+                inner.rejected += 1;
+                return Err(until - now);
+            }
+            State::Open { .. } => {
+                inner.state = State::HalfOpen;
+            }
+            State::HalfOpen => {
+                // 시험 요청의 결과가 나올 때까지는 다른 요청을 보내지 않는다.
+                inner.rejected += 1;
+                return Err(Duration::ZERO);
+            }
+        }
+
+        Ok(Permit {
+            breaker: self.clone(),
+            trips: inner.trips,
+            done: false,
+        })
+    }
+
+    fn record(&self, trips: u64, success: bool) {
+        let mut inner = self.inner.lock().unwrap();
+
+        // 서킷이 열리기 전에 보낸 느린 요청이 늦게 끝나도 쉬는 시간을 줄이지 않는다.
+        // 열린 뒤의 허가는 HalfOpen에서 보낸 시험 요청 하나뿐이다.
+        if trips != inner.trips {
+            return;
+        }
+
+        if success {
+            inner.failures = 0;
+            inner.state = State::Closed;
+            return;
+        }
+
+        inner.failures += 1;
+        inner.state = match inner.state {
+            State::Closed if inner.failures < self.config.failure_threshold => State::Closed,
+            // 이미 열려 있으면 처음 연 시각을 그대로 둔다.
+            State::Open { until } => State::Open { until },
+            State::Closed | State::HalfOpen => {
+                inner.trips += 1;
+                tracing::warn!(
+                    "서킷 열림: {:?} 동안 업스트림 요청 중단",
+                    self.config.cool_down
+                );
+                State::Open {
+                    until: Instant::now() + self.config.cool_down,
+                }
+            }
+        };
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+        let (state, retry_in_secs) = match inner.state {
+            State::Closed => ("closed", None),
+            State::Open { until } => (
+                "open",
+                Some(until.saturating_duration_since(Instant::now()).as_secs()),
+            ),
+            State::HalfOpen => ("half_open", None),
+        };
+
+        BreakerStatus {
+            state,
+            consecutive_failures: inner.failures,
+            failure_threshold: self.config.failure_threshold,
+            retry_in_secs,
+            trips: inner.trips,
+            rejected: inner.rejected,
+        }
+    }
+}
+
+impl Permit {
+    pub fn success(mut self) {
+        self.done = true;
+        self.breaker.record(self.trips, true);
+    }
+
+    pub fn failure(mut self) {
+        self.done = true;
+        self.breaker.record(self.trips, false);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.done {
+            self.breaker.record(self.trips, false);
+        }
+    }
+}
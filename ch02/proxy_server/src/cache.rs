@@ -0,0 +1,331 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, HeaderName, StatusCode, header},
+};
+use serde::Serialize;
+
+/// 캐시 설정
+#[derive(Clone)]
+pub struct CacheConfig {
+    /// 업스트림이 캐시 기간을 알려주지 않았을 때 쓰는 기간
+    pub default_ttl: Duration,
+    /// 캐시에 저장할 수 있는 본문 크기의 합
+    pub max_bytes: usize,
+    /// 만료된 뒤에도 남겨두는 기간.
+    /// 이 기간 안에는 업스트림에 변경 여부만 확인하고, 업스트림에 요청할 수 없으면 대신 돌려준다.
+    pub max_stale: Duration,
+    /// 업스트림으로 전달하고 캐시 키에도 넣는 요청 헤더.
+    /// 업스트림이 `Vary`로 알려주는 헤더를 여기에 넣는다.
+    pub vary_headers: Vec<HeaderName>,
+}
+
+/// 캐시에 저장하는 응답
+#[derive(Clone)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    /// 홉 단위 헤더와 `Set-Cookie`처럼 사용자마다 다른 헤더를 뺀 업스트림 응답 헤더
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+struct Entry {
+    response: CachedResponse,
+    inserted_at: Instant,
+    expires_at: Instant,
+    /// `Inner::lru`에서 이 항목을 찾을 때 쓰는 값
+    last_used: u64,
+    hits: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    /// 마지막으로 사용한 순서대로 정렬된 키. 맨 앞이 가장 오래전에 쓴 항목이다.
+    lru: BTreeMap<u64, String>,
+    tick: u64,
+    bytes: usize,
+}
+
+impl Inner {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.lru.remove(&entry.last_used);
+        self.bytes -= entry.response.body.len();
+        Some(entry)
+    }
+}
+
+/// TTL과 전체 크기 제한이 있는 LRU 캐시
+#[derive(Clone)]
+pub struct Cache {
+    inner: Arc<Mutex<Inner>>,
+    config: CacheConfig,
+}
+
+/// 관리자 API에서 보여주는 캐시 항목 정보
+#[derive(Serialize)]
+pub struct EntryInfo {
+    pub key: String,
+    pub status: u16,
+    pub size: usize,
+    pub hits: u64,
+    pub age_secs: u64,
+    pub expires_in_secs: u64,
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Self {
+        Cache {
+            inner: Arc::default(),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// 만료되지 않은 항목을 가져온다.
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lookup(key, Duration::ZERO)
+    }
+
+    /// 만료된 지 `max_stale`이 지나지 않은 항목까지 가져온다.
+    /// 업스트림에 요청할 수 없을 때 오래된 응답이라도 돌려주려고 쓴다.
+    pub fn get_stale(&self, key: &str) -> Option<CachedResponse> {
+        self.lookup(key, self.config.max_stale)
+    }
+
+    /// 만료된 지 `stale`이 지나지 않은 항목을 가져온다.
+    /// `max_stale`보다 오래 전에 만료된 항목은 이때 지운다.
+    fn lookup(&self, key: &str, stale: Duration) -> Option<CachedResponse> {
+        let mut inner = self.inner.lock().unwrap();
+        let tick = inner.next_tick();
+        let now = Instant::now();
+
+        let entry = inner.entries.get_mut(key)?;
+        if entry.expires_at + self.config.max_stale <= now {
+            inner.remove(key);
+            return None;
+        }
+        if entry.expires_at + stale <= now {
+            return None;
+        }
+
+        let previous = entry.last_used;
+        entry.last_used = tick;
+        entry.hits += 1;
+        let response = entry.response.clone();
+
+        inner.lru.remove(&previous);
+        inner.lru.insert(tick, key.to_string());
+
+        Some(response)
+    }
+
+    /// 항목을 저장한다.
+    /// 전체 크기가 제한을 넘으면 가장 오래전에 사용한 항목부터 지운다.
+    /// `ttl`이 0인 항목은 곧바로 만료되어 `max_stale` 동안 변경 여부 확인에만 쓰인다.
+    pub fn insert(&self, key: String, response: CachedResponse, ttl: Duration) {
+        let size = response.body.len();
+        if (ttl + self.config.max_stale).is_zero() || size > self.config.max_bytes {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(&key);
+
+        while inner.bytes + size > self.config.max_bytes {
+            let Some((_, oldest)) = inner.lru.pop_first() else {
+                break;
+            };
+            if let Some(entry) = inner.entries.remove(&oldest) {
+                inner.bytes -= entry.response.body.len();
+            }
+        }
+
+        let tick = inner.next_tick();
+        let now = Instant::now();
+        inner.lru.insert(tick, key.clone());
+        inner.bytes += size;
+        inner.entries.insert(
+            key,
+            Entry {
+                response,
+                inserted_at: now,
+                expires_at: now + ttl,
+                last_used: tick,
+                hits: 0,
+            },
+        );
+    }
+
+    /// 항목을 지운다. 지운 항목이 있으면 true를 반환한다.
+    pub fn remove(&self, key: &str) -> bool {
+        self.inner.lock().unwrap().remove(key).is_some()
+    }
+
+    /// 모든 항목을 지우고 지운 개수를 반환한다.
+    pub fn clear(&self) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let count = inner.entries.len();
+        inner.entries.clear();
+        inner.lru.clear();
+        inner.bytes = 0;
+        count
+    }
+
+    /// 저장된 본문 크기의 합
+    pub fn bytes(&self) -> usize {
+        self.inner.lock().unwrap().bytes
+    }
+
+    /// 최근에 사용한 순서대로 항목 정보를 반환한다.
+    pub fn entries(&self) -> Vec<EntryInfo> {
+        let inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+
+        inner
+            .lru
+            .values()
+            .rev()
+            .filter_map(|key| inner.entries.get(key).map(|entry| (key, entry)))
+            .map(|(key, entry)| EntryInfo {
+                key: key.clone(),
+                status: entry.response.status.as_u16(),
+                size: entry.response.body.len(),
+                hits: entry.hits,
+                age_secs: now.duration_since(entry.inserted_at).as_secs(),
+                expires_in_secs: entry.expires_at.saturating_duration_since(now).as_secs(),
+            })
+            .collect()
+    }
+}
+
+/// 캐시 키를 만든다.
+/// 업스트림 요청을 결정하는 메서드와 URL 전체에 `vary_headers`로 지정한 요청 헤더의 값을 더한다.
+/// 헤더 이름은 정렬하고, 값이 없는 헤더도 키에 남겨서 "헤더 없음"과 "빈 값"을 구분한다.
+pub fn cache_key(
+    method: &str,
+    url: &str,
+    headers: &HeaderMap,
+    vary_headers: &[HeaderName],
+) -> String {
+    let mut vary_headers = vary_headers.iter().collect::<Vec<_>>();
+    vary_headers.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    vary_headers.dedup();
+
+    let mut key = format!("{method} {url}");
+    for name in vary_headers {
+        let values = headers
+            .get_all(name)
+            .iter()
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            key.push_str(&format!("\n{name}"));
+        } else {
+            key.push_str(&format!("\n{name}: {}", values.join(", ")));
+        }
+    }
+
+    key
+}
+
+/// `Cache-Control`의 지시어를 소문자로 바꿔서 하나씩 돌려준다.
+fn cache_directives(headers: &HeaderMap) -> impl Iterator<Item = String> + '_ {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_ascii_lowercase())
+}
+
+/// 만료된 뒤에는 업스트림에 확인하지 않고 돌려주면 안 되는 응답인지 확인한다.
+/// `no-cache`나 `must-revalidate`가 있는 응답은 서킷이 열려 있어도 만료된 채로 돌려주지 않는다.
+pub fn must_revalidate(headers: &HeaderMap) -> bool {
+    cache_directives(headers).any(|directive| {
+        matches!(directive.as_str(), "no-cache" | "must-revalidate")
+            || directive.starts_with("no-cache=")
+    })
+}
+
+/// 요청 헤더에서 `vary_headers`로 지정한 헤더만 골라낸다.
+/// 캐시를 거치는 요청은 이 헤더만 업스트림으로 보내서, 캐시 키에 없는 헤더로 응답이 달라지지 않게 한다.
+pub fn keyed_headers(headers: &HeaderMap, vary_headers: &[HeaderName]) -> HeaderMap {
+    let mut keyed = HeaderMap::new();
+    for name in vary_headers {
+        for value in headers.get_all(name) {
+            keyed.append(name, value.clone());
+        }
+    }
+    keyed
+}
+
+/// 업스트림 응답 헤더를 보고 캐시할 기간을 정한다.
+/// `Cache-Control`이 `Expires`보다 우선하고, 둘 다 없으면 `default_ttl`을 쓴다.
+/// 캐시하면 안 되는 응답이면 None을 반환한다.
+///
+/// `no-cache`와 `max-age=0`은 저장은 하되 매번 업스트림에 확인하라는 뜻이므로 0을 반환한다.
+/// 확인할 `ETag`나 `Last-Modified`가 없으면 저장해도 쓸 수 없으므로 None을 반환한다.
+pub fn cache_ttl(headers: &HeaderMap, default_ttl: Duration) -> Option<Duration> {
+    if headers.contains_key(header::CACHE_CONTROL) {
+        let mut max_age = None;
+        let mut shared_max_age = None;
+        let mut no_cache = false;
+
+        for directive in cache_directives(headers) {
+            match directive.split_once('=') {
+                Some(("max-age", seconds)) => max_age = seconds.trim_matches('"').parse().ok(),
+                Some(("s-maxage", seconds)) => {
+                    shared_max_age = seconds.trim_matches('"').parse().ok()
+                }
+                // `no-cache="Set-Cookie"`처럼 헤더를 지정해도 응답 전체를 확인하도록 다룬다.
+                Some(("no-cache", _)) => no_cache = true,
+                None if directive == "no-cache" => no_cache = true,
+                None if matches!(directive.as_str(), "no-store" | "private") => return None,
+                _ => {}
+            }
+        }
+
+        let has_validators =
+            headers.contains_key(header::ETAG) || headers.contains_key(header::LAST_MODIFIED);
+        if no_cache {
+            return has_validators.then_some(Duration::ZERO);
+        }
+
+        // 공유 캐시에는 s-maxage가 max-age보다 우선한다.
+        if let Some(seconds) = shared_max_age.or(max_age) {
+            if seconds == 0 {
+                return has_validators.then_some(Duration::ZERO);
+            }
+            return Some(Duration::from_secs(seconds));
+        }
+    }
+
+    if let Some(expires) = headers.get(header::EXPIRES) {
+        // 형식이 잘못된 Expires는 이미 만료된 것으로 본다.
+        let expires = expires
+            .to_str()
+            .ok()
+            .and_then(|value| httpdate::parse_http_date(value).ok())?;
+        return expires
+            .duration_since(SystemTime::now())
+            .ok()
+            .filter(|ttl| !ttl.is_zero());
+    }
+
+    Some(default_ttl)
+}
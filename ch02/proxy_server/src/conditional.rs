@@ -0,0 +1,116 @@
+//! 조건부 요청
+//!
+//! - 만료된 항목은 업스트림에 `If-None-Match`/`If-Modified-Since`로 다시 확인하고,
+//!   304를 받으면 저장한 본문을 그대로 쓴다.
+//! - 클라이언트가 보낸 조건부 요청은 캐시한 응답으로 직접 확인해서 304로 답한다.
+
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+
+use crate::{cache::CachedResponse, upstream::remove_hop_by_hop};
+
+/// 클라이언트가 보낸 조건부 요청 헤더.
+/// 캐시를 거치는 요청에서는 업스트림으로 전달하지 않고 프록시가 직접 처리한다.
+/// 일부만 받는 `Range` 요청도 캐시에 일부만 저장되지 않도록 전달하지 않는다.
+pub const CLIENT_CONDITIONALS: [header::HeaderName; 6] = [
+    header::IF_NONE_MATCH,
+    header::IF_MODIFIED_SINCE,
+    header::IF_MATCH,
+    header::IF_UNMODIFIED_SINCE,
+    header::IF_RANGE,
+    header::RANGE,
+];
+
+/// 저장한 응답을 업스트림에 다시 확인할 때 보낼 헤더를 만든다.
+/// 응답에 `ETag`도 `Last-Modified`도 없으면 빈 헤더를 반환한다.
+pub fn validators(response: &CachedResponse) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(etag) = response.headers.get(header::ETAG) {
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+    }
+    if let Some(last_modified) = response.headers.get(header::LAST_MODIFIED) {
+        headers.insert(header::IF_MODIFIED_SINCE, last_modified.clone());
+    }
+    headers
+}
+
+/// 업스트림이 304로 답하면 저장한 응답의 헤더를 304 응답의 헤더로 갱신한다.
+/// 본문은 그대로 쓴다.
+pub fn refresh(stored: CachedResponse, not_modified: &HeaderMap) -> CachedResponse {
+    let mut updates = not_modified.clone();
+    remove_hop_by_hop(&mut updates);
+    updates.remove(header::CONTENT_LENGTH);
+
+    let mut headers = stored.headers;
+    for name in updates.keys() {
+        headers.remove(name);
+        for value in updates.get_all(name) {
+            headers.append(name, value.clone());
+        }
+    }
+
+    CachedResponse { headers, ..stored }
+}
+
+/// `W/`를 뺀 ETag 값. 약한 비교에 쓴다.
+fn opaque_tag(etag: &str) -> &str {
+    etag.trim().trim_start_matches("W/")
+}
+
+/// 클라이언트가 이미 가진 응답과 같아서 304로 답해도 되는지 확인한다.
+/// `If-None-Match`가 있으면 ETag만 비교하고, 없을 때만 `If-Modified-Since`를 본다.
+pub fn is_not_modified(request: &HeaderMap, response: &CachedResponse) -> bool {
+    if response.status != StatusCode::OK {
+        return false;
+    }
+
+    if let Some(if_none_match) = request.get(header::IF_NONE_MATCH) {
+        let Some(etag) = response
+            .headers
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return false;
+        };
+        return if_none_match.to_str().is_ok_and(|tags| {
+            tags.trim() == "*"
+                || tags
+                    .split(',')
+                    .any(|tag| opaque_tag(tag) == opaque_tag(etag))
+        });
+    }
+
+    let parse = |value: Option<&HeaderValue>| {
+        value
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value).ok())
+    };
+    match (
+        parse(request.get(header::IF_MODIFIED_SINCE)),
+        parse(response.headers.get(header::LAST_MODIFIED)),
+    ) {
+        (Some(since), Some(last_modified)) => last_modified <= since,
+        _ => false,
+    }
+}
+
+/// 304 응답에 함께 보내는 헤더
+const NOT_MODIFIED_HEADERS: [header::HeaderName; 7] = [
+    header::CACHE_CONTROL,
+    header::CONTENT_LOCATION,
+    header::DATE,
+    header::ETAG,
+    header::EXPIRES,
+    header::LAST_MODIFIED,
+    header::VARY,
+];
+
+/// 클라이언트에게 보낼 304 응답의 헤더를 만든다.
+pub fn not_modified_headers(response: &CachedResponse) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for name in &NOT_MODIFIED_HEADERS {
+        for value in response.headers.get_all(name) {
+            headers.append(name, value.clone());
+        }
+    }
+    headers
+}
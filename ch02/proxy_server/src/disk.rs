@@ -0,0 +1,347 @@
+//! 디스크 캐시
+//!
+//! 메모리 캐시 다음에 찾아보는 두 번째 캐시로, 서버를 다시 시작해도 남는다.
+//!
+//! - 응답 본문은 `objects/{SHA-256}` 파일에 저장한다. 본문이 같으면 키가 달라도 파일 하나를 함께 쓴다.
+//! - 키와 본문 파일, 상태 코드, 만료 시각은 `index.json`에 저장한다.
+//! - 본문 파일 크기의 합이 제한을 넘으면 가장 오래전에 사용한 항목부터 지운다.
+//!   만료된 지 `max_stale`이 지난 항목은 이때 함께 지운다.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::ErrorKind,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::Bytes,
+    http::{HeaderName, HeaderValue, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::cache::{Cache, CachedResponse};
+
+/// 디스크 캐시 설정
+#[derive(Clone)]
+pub struct DiskCacheConfig {
+    /// 인덱스와 본문 파일을 저장할 디렉터리
+    pub dir: PathBuf,
+    /// 디스크에 저장할 수 있는 본문 크기의 합
+    pub max_bytes: u64,
+    /// 만료된 뒤에도 남겨두는 기간. 메모리 캐시의 `max_stale`과 같은 값을 쓴다.
+    pub max_stale: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexEntry {
+    /// 본문의 SHA-256. 본문 파일 이름으로 쓴다.
+    hash: String,
+    status: u16,
+    /// 응답 헤더. 값이 UTF-8이 아닌 헤더는 저장하지 않는다.
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    size: u64,
+    /// 만료 시각. 서버를 다시 시작해도 쓸 수 있도록 UNIX 시간(초)으로 저장한다.
+    expires_at: u64,
+    /// 마지막으로 사용한 순서. 클수록 최근에 쓴 항목이다.
+    last_used: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    entries: HashMap<String, IndexEntry>,
+    tick: u64,
+}
+
+impl Index {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// 본문 파일 크기의 합. 여러 키가 함께 쓰는 파일은 한 번만 센다.
+    fn bytes(&self) -> u64 {
+        let mut seen = HashSet::new();
+        self.entries
+            .values()
+            .filter(|entry| seen.insert(&entry.hash))
+            .map(|entry| entry.size)
+            .sum()
+    }
+
+    fn is_referenced(&self, hash: &str) -> bool {
+        self.entries.values().any(|entry| entry.hash == hash)
+    }
+}
+
+/// 디스크 캐시
+#[derive(Clone)]
+pub struct DiskCache {
+    config: DiskCacheConfig,
+    /// 인덱스 파일은 이 잠금을 잡은 채로만 쓴다.
+    index: Arc<Mutex<Index>>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl DiskCache {
+    /// 디렉터리를 만들고 인덱스를 읽는다.
+    /// 본문 파일이 없는 항목은 인덱스에서 지우고, 인덱스에 없는 본문 파일은 디스크에서 지운다.
+    pub async fn open(config: DiskCacheConfig) -> std::io::Result<Self> {
+        let objects = config.dir.join("objects");
+        tokio::fs::create_dir_all(&objects).await?;
+
+        let mut index = match tokio::fs::read(config.dir.join("index.json")).await {
+            Ok(index) => serde_json::from_slice::<Index>(&index).unwrap_or_else(|err| {
+                tracing::warn!("디스크 캐시 인덱스를 읽지 못해서 비웁니다: {err}");
+                Index::default()
+            }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Index::default(),
+            Err(err) => return Err(err),
+        };
+
+        let mut stored = HashSet::new();
+        let mut dir = tokio::fs::read_dir(&objects).await?;
+        while let Some(file) = dir.next_entry().await? {
+            stored.insert(file.file_name().to_string_lossy().into_owned());
+        }
+
+        index
+            .entries
+            .retain(|_, entry| stored.contains(&entry.hash));
+        for hash in stored {
+            if !index.is_referenced(&hash) {
+                tokio::fs::remove_file(objects.join(&hash)).await?;
+            }
+        }
+
+        let cache = DiskCache {
+            config,
+            index: Arc::new(Mutex::new(index)),
+        };
+        let mut index = cache.index.lock().await;
+        cache.evict(&mut index, 0).await?;
+        cache.save(&index).await?;
+        drop(index);
+
+        Ok(cache)
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.config.dir.join("objects").join(hash)
+    }
+
+    /// 인덱스를 임시 파일에 쓰고 이름을 바꿔서, 쓰다가 멈춰도 이전 인덱스가 남게 한다.
+    async fn save(&self, index: &Index) -> std::io::Result<()> {
+        let path = self.config.dir.join("index.json");
+        let tmp = self.config.dir.join("index.json.tmp");
+        let json = serde_json::to_vec(index).expect("Index is serializable");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(tmp, path).await
+    }
+
+    /// 본문 파일을 지운다. 이미 없으면 지운 것으로 본다.
+    async fn remove_object(&self, hash: &str) -> std::io::Result<()> {
+        match tokio::fs::remove_file(self.object_path(hash)).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// 항목 하나를 인덱스에서 지우고, 다른 항목이 쓰지 않는 본문 파일도 지운다.
+    async fn remove_entry(&self, index: &mut Index, key: &str) -> std::io::Result<bool> {
+        let Some(entry) = index.entries.remove(key) else {
+            return Ok(false);
+        };
+        if !index.is_referenced(&entry.hash) {
+            self.remove_object(&entry.hash).await?;
+        }
+        Ok(true)
+    }
+
+    /// 만료된 지 `max_stale`이 지난 항목을 지우고,
+    /// `incoming` 바이트를 더 저장할 수 있을 때까지 가장 오래전에 사용한 항목부터 지운다.
+    /// 항목은 한 번만 정렬하고, 남은 크기와 본문 파일을 쓰는 항목 수는 지우면서 따로 센다.
+    async fn evict(&self, index: &mut Index, incoming: u64) -> std::io::Result<()> {
+        let expired_before = now_secs().saturating_sub(self.config.max_stale.as_secs());
+        let mut bytes = index.bytes();
+        let mut references = HashMap::<String, usize>::new();
+        for entry in index.entries.values() {
+            *references.entry(entry.hash.clone()).or_default() += 1;
+        }
+
+        // 너무 오래 만료된 항목이 앞에 오고, 그 안에서는 오래전에 사용한 항목이 앞에 온다.
+        let mut candidates = index
+            .entries
+            .iter()
+            .map(|(key, entry)| {
+                let expired = entry.expires_at <= expired_before;
+                (!expired, entry.last_used, key.clone())
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        for (fresh, _, key) in candidates {
+            if fresh && bytes + incoming <= self.config.max_bytes {
+                break;
+            }
+            let Some(entry) = index.entries.remove(&key) else {
+                continue;
+            };
+            let count = references
+                .get_mut(&entry.hash)
+                .expect("every entry hash is counted");
+            *count -= 1;
+            if *count == 0 {
+                bytes -= entry.size;
+                self.remove_object(&entry.hash).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 만료된 지 `stale`이 지나지 않은 항목을 가져온다.
+    /// 남은 캐시 기간도 함께 반환한다. 만료된 항목이면 0이다.
+    /// 사용 순서는 인덱스를 다음에 저장할 때 함께 기록된다.
+    pub async fn get(&self, key: &str, stale: Duration) -> Option<(CachedResponse, Duration)> {
+        let (hash, status, headers, expires_at) = {
+            let mut index = self.index.lock().await;
+            let tick = index.next_tick();
+            let entry = index.entries.get_mut(key)?;
+            if entry.expires_at + stale.as_secs() <= now_secs() {
+                return None;
+            }
+            entry.last_used = tick;
+            (
+                entry.hash.clone(),
+                entry.status,
+                entry.headers.clone(),
+                entry.expires_at,
+            )
+        };
+
+        // 읽는 사이에 지워졌으면 없는 것으로 본다.
+        let body = tokio::fs::read(self.object_path(&hash)).await.ok()?;
+        let response = CachedResponse {
+            status: StatusCode::from_u16(status).ok()?,
+            headers: headers
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    Some((
+                        HeaderName::try_from(name).ok()?,
+                        HeaderValue::try_from(value).ok()?,
+                    ))
+                })
+                .collect(),
+            body: Bytes::from(body),
+        };
+        let ttl = Duration::from_secs(expires_at.saturating_sub(now_secs()));
+
+        Some((response, ttl))
+    }
+
+    /// 항목을 저장한다. 전체 크기가 제한을 넘으면 가장 오래전에 사용한 항목부터 지운다.
+    /// `ttl`이 0인 항목은 만료된 채로 `max_stale` 동안 저장되어 변경 여부 확인에만 쓰인다.
+    pub async fn insert(
+        &self,
+        key: String,
+        response: &CachedResponse,
+        ttl: Duration,
+    ) -> std::io::Result<()> {
+        let size = response.body.len() as u64;
+        if (ttl + self.config.max_stale).is_zero() || size > self.config.max_bytes {
+            return Ok(());
+        }
+
+        let hash = hex::encode(Sha256::digest(&response.body));
+        let mut index = self.index.lock().await;
+        self.remove_entry(&mut index, &key).await?;
+
+        let stored = index.is_referenced(&hash);
+        if !stored {
+            self.evict(&mut index, size).await?;
+            let path = self.object_path(&hash);
+            let tmp = path.with_extension("tmp");
+            tokio::fs::write(&tmp, &response.body).await?;
+            tokio::fs::rename(tmp, path).await?;
+        }
+
+        let tick = index.next_tick();
+        index.entries.insert(
+            key,
+            IndexEntry {
+                hash,
+                status: response.status.as_u16(),
+                headers: response
+                    .headers
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                size,
+                expires_at: now_secs() + ttl.as_secs(),
+                last_used: tick,
+            },
+        );
+        self.save(&index).await
+    }
+
+    /// 항목을 지운다. 지운 항목이 있으면 true를 반환한다.
+    pub async fn remove(&self, key: &str) -> std::io::Result<bool> {
+        let mut index = self.index.lock().await;
+        let removed = self.remove_entry(&mut index, key).await?;
+        if removed {
+            self.save(&index).await?;
+        }
+        Ok(removed)
+    }
+
+    /// 모든 항목을 지우고 지운 개수를 반환한다.
+    pub async fn clear(&self) -> std::io::Result<usize> {
+        let mut index = self.index.lock().await;
+        let keys = index.entries.keys().cloned().collect::<Vec<_>>();
+        for key in &keys {
+            self.remove_entry(&mut index, key).await?;
+        }
+        self.save(&index).await?;
+        Ok(keys.len())
+    }
+
+    /// 저장된 본문 크기의 합
+    pub async fn bytes(&self) -> u64 {
+        self.index.lock().await.bytes()
+    }
+
+    /// 만료되지 않은 항목을 메모리 캐시에 채운다. 채운 개수를 반환한다.
+    /// 메모리 캐시가 작으면 최근에 사용한 항목이 남도록 오래된 항목부터 넣는다.
+    pub async fn warm(&self, cache: &Cache) -> usize {
+        let mut entries = {
+            let index = self.index.lock().await;
+            index
+                .entries
+                .iter()
+                .map(|(key, entry)| (entry.last_used, key.clone()))
+                .collect::<Vec<_>>()
+        };
+        entries.sort();
+
+        let mut warmed = 0;
+        for (_, key) in entries {
+            if let Some((response, ttl)) = self.get(&key, Duration::ZERO).await {
+                cache.insert(key, response, ttl);
+                warmed += 1;
+            }
+        }
+        warmed
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::broadcast;
+
+type Inflight<T> = Arc<Mutex<HashMap<String, broadcast::Sender<Result<T, FlightError>>>>>;
+
+/// 같은 키로 동시에 들어온 요청을 하나로 합친다.
+/// 처음 들어온 요청만 작업을 실행하고, 나머지는 그 결과를 함께 받는다.
+pub struct SingleFlight<T> {
+    inflight: Inflight<T>,
+}
+
+impl<T> Clone for SingleFlight<T> {
+    fn clone(&self) -> Self {
+        SingleFlight {
+            inflight: self.inflight.clone(),
+        }
+    }
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        SingleFlight {
+            inflight: Arc::default(),
+        }
+    }
+}
+
+/// 합쳐진 요청을 기다리다가 실패한 이유
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlightError {
+    /// `timeout` 안에 결과를 받지 못했다.
+    Timeout,
+    /// 작업이 결과를 보내지 못하고 끝났다.
+    Aborted,
+}
+
+impl<T: Clone + Send + 'static> SingleFlight<T> {
+    /// `key`로 실행 중인 작업이 있으면 그 결과를 기다리고, 없으면 `work`를 실행한다.
+    /// 작업은 별도의 태스크에서 실행하므로 처음 요청한 클라이언트가 연결을 끊어도
+    /// 기다리던 다른 요청은 결과를 받는다. 작업도 `timeout`이 지나면 취소된다.
+    pub async fn run<F, Fut>(&self, key: &str, timeout: Duration, work: F) -> Result<T, FlightError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let mut receiver = {
+            let mut inflight = self.inflight.lock().unwrap();
+            match inflight.get(key) {
+                Some(sender) => sender.subscribe(),
+                None => {
+                    let (sender, receiver) = broadcast::channel(1);
+                    inflight.insert(key.to_string(), sender.clone());
+
+                    let inflight = self.inflight.clone();
+                    let key = key.to_string();
+                    let work = work();
+                    tokio::spawn(async move {
+                        let result = tokio::time::timeout(timeout, work).await;
+                        // 결과를 보내기 전에 지워야 이후 요청이 끝난 작업에 합쳐지지 않는다.
+                        inflight.lock().unwrap().remove(&key);
+                        let _ = sender.send(result.map_err(|_| FlightError::Timeout));
+                    });
+
+                    receiver
+                }
+            }
+        };
+
+        match tokio::time::timeout(timeout, receiver.recv()).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(FlightError::Aborted),
+            Err(_) => Err(FlightError::Timeout),
+        }
+    }
+}
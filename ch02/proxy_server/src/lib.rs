@@ -0,0 +1,54 @@
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
+
+use crate::{
+    admin::{get_breaker, get_cache, purge_cache},
+    metrics::metrics,
+    proxy::proxy_handler,
+    ratelimit::{RateLimiter, rate_limit},
+    reverse::reverse_proxy,
+    state::AppState,
+};
+
+pub mod admin;
+pub mod breaker;
+pub mod cache;
+pub mod conditional;
+pub mod disk;
+pub mod flight;
+pub mod metrics;
+pub mod proxy;
+pub mod ratelimit;
+pub mod reverse;
+pub mod state;
+pub mod upstream;
+
+/// 프록시 서버의 라우터를 만든다.
+/// 다른 경로에 맞지 않은 요청은 리버스 프록시로 보낸다.
+/// 프록시 경로와 관리 경로에는 요청 수 제한을 따로 건다.
+pub fn router(state: AppState) -> Router {
+    let proxy = limited(
+        Router::new()
+            .route("/", post(proxy_handler).fallback(reverse_proxy))
+            .fallback(reverse_proxy),
+        &state.rate_limits.proxy,
+    );
+    let admin = limited(
+        Router::new()
+            .route("/admin/cache", get(get_cache).delete(purge_cache))
+            .route("/admin/breaker", get(get_breaker))
+            .route("/metrics", get(metrics)),
+        &state.rate_limits.admin,
+    );
+
+    proxy.merge(admin).with_state(state)
+}
+
+fn limited(router: Router<AppState>, limiter: &Option<RateLimiter>) -> Router<AppState> {
+    match limiter {
+        Some(limiter) => router.layer(middleware::from_fn_with_state(limiter.clone(), rate_limit)),
+        None => router,
+    }
+}
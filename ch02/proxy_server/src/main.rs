@@ -1,55 +1,118 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
-
-use axum::{Json, Router, body::Bytes, extract::State, http::StatusCode, routing::post};
-use reqwest::Client;
+use std::{env, net::SocketAddr, sync::Arc, time::Duration};
 
-type Cache = Arc<Mutex<HashMap<String, Bytes>>>;
+use axum::http::HeaderName;
+use proxy_server::{
+    breaker::BreakerConfig,
+    cache::{Cache, CacheConfig},
+    disk::{DiskCache, DiskCacheConfig},
+    ratelimit::{MemoryStore, RateLimits},
+    reverse::Routes,
+    router,
+    state::AppState,
+    upstream::UpstreamConfig,
+};
+use tracing_subscriber::EnvFilter;
 
-#[derive(serde::Deserialize)]
-struct Data {
-    breed: String,
-    num_pics: Option<i32>,
+/// 환경 변수를 숫자나 true/false로 읽는다. 없으면 기본값을 쓴다.
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid value for {key}"))
+        })
+        .unwrap_or(default)
 }
 
-async fn proxy_handler(State(state): State<Cache>, Json(data): Json<Data>) -> (StatusCode, Bytes) {
-    if let Some(body) = state.lock().unwrap().get(&data.breed) {
-        println!("{} 캐시 히트", data.breed);
-        return (StatusCode::OK, body.clone());
-    }
+#[tokio::main]
+async fn main() {
+    // RUST_LOG이 없으면 요청마다 남기는 캐시 로그까지 보여준다.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("info,proxy_server=debug")),
+        )
+        .init();
 
-    println!("{} 캐시 미스", data.breed);
+    let cache = Cache::new(CacheConfig {
+        default_ttl: Duration::from_secs(env_or("PROXY_CACHE_TTL_SECS", 300)),
+        max_bytes: env_or("PROXY_CACHE_MAX_BYTES", 16 * 1024 * 1024),
+        max_stale: Duration::from_secs(env_or("PROXY_CACHE_MAX_STALE_SECS", 3600)),
+        vary_headers: env::var("PROXY_CACHE_VARY")
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| {
+                        HeaderName::try_from(name).unwrap_or_else(|_| {
+                            panic!("Invalid header name in PROXY_CACHE_VARY: {name}")
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    });
+    let upstream = UpstreamConfig {
+        base_url: env::var("PROXY_UPSTREAM_BASE_URL")
+            .unwrap_or_else(|_| "https://dog.ceo".to_string()),
+        timeout: Duration::from_secs(env_or("PROXY_UPSTREAM_TIMEOUT_SECS", 10)),
+        connect_timeout: Duration::from_secs(env_or("PROXY_UPSTREAM_CONNECT_TIMEOUT_SECS", 3)),
+        read_timeout: Duration::from_secs(env_or("PROXY_UPSTREAM_READ_TIMEOUT_SECS", 5)),
+        max_retries: env_or("PROXY_UPSTREAM_MAX_RETRIES", 2),
+        retry_base_delay: Duration::from_millis(env_or("PROXY_UPSTREAM_RETRY_DELAY_MS", 100)),
+        // 0이면 실패 응답을 캐시하지 않는다.
+        negative_ttl: Some(Duration::from_secs(env_or(
+            "PROXY_NEGATIVE_CACHE_TTL_SECS",
+            0,
+        )))
+        .filter(|ttl| !ttl.is_zero()),
+    };
+    let breaker = BreakerConfig {
+        failure_threshold: env_or("PROXY_BREAKER_FAILURE_THRESHOLD", 5),
+        cool_down: Duration::from_secs(env_or("PROXY_BREAKER_COOL_DOWN_SECS", 30)),
+    };
+    let mut state = AppState::new(cache.clone(), upstream, breaker);
 
-    let url = format!(
-        "https://dog.ceo/api/breed/{}/images/random{}",
-        &data.breed,
-        &data
-            .num_pics
-            .map(|num| format!("/{}", num))
-            .unwrap_or(String::new())
-    );
+    // PROXY_DISK_CACHE_DIR을 설정하면 디스크 캐시를 함께 쓴다.
+    if let Ok(dir) = env::var("PROXY_DISK_CACHE_DIR") {
+        let disk = DiskCache::open(DiskCacheConfig {
+            dir: dir.into(),
+            max_bytes: env_or("PROXY_DISK_CACHE_MAX_BYTES", 256 * 1024 * 1024),
+            max_stale: cache.config().max_stale,
+        })
+        .await
+        .expect("Failed to open disk cache");
 
-    let client = Client::new();
-    let res = client.get(url).send().await.unwrap();
+        if env_or("PROXY_DISK_CACHE_WARM", true) {
+            let warmed = disk.warm(&cache).await;
+            tracing::info!("디스크 캐시에서 {warmed}개 항목을 메모리로 읽음");
+        }
+        state = state.with_disk_cache(disk);
+    }
 
-    let code = res.status().as_u16();
-    let body = res.bytes().await.unwrap();
-    let mut cache = state.lock().unwrap();
-    cache.insert(data.breed, body.clone());
+    // PROXY_ROUTES="/api=http://localhost:9000,/img=http://localhost:9001"
+    if let Ok(spec) = env::var("PROXY_ROUTES") {
+        let routes =
+            Routes::parse(&spec).unwrap_or_else(|err| panic!("Invalid PROXY_ROUTES: {err}"));
+        if !routes.is_empty() {
+            tracing::info!("리버스 프록시 경로: {spec}");
+        }
+        state = state.with_routes(routes);
+    }
 
-    (StatusCode::from_u16(code).unwrap(), body)
-}
+    state = state.with_rate_limits(RateLimits::from_env(Arc::new(MemoryStore::default())));
 
-#[tokio::main]
-async fn main() {
-    let state = Arc::new(Mutex::new(HashMap::<String, Bytes>::new()));
-    let app = Router::new()
-        .route("/", post(proxy_handler))
-        .with_state(state);
+    let app = router(state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await.unwrap();
 
-    axum::serve(listener, app).await.unwrap();
+    // X-Forwarded-For에 넣고 요청 수 제한에 쓸 클라이언트 주소를 핸들러에서 알 수 있게 한다.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+
+use crate::{breaker::CircuitBreaker, cache::Cache};
+
+/// "GET /metrics" 핸들러
+/// Prometheus 텍스트 형식으로 캐시와 서킷 브레이커 상태를 내보낸다.
+pub async fn metrics(
+    State(cache): State<Cache>,
+    State(breaker): State<CircuitBreaker>,
+) -> impl IntoResponse {
+    let status = breaker.status();
+    let mut body = String::new();
+
+    let mut metric = |name: &str, help: &str, kind: &str, value: u64| {
+        let _ = writeln!(body, "# HELP {name} {help}");
+        let _ = writeln!(body, "# TYPE {name} {kind}");
+        let _ = writeln!(body, "{name} {value}");
+    };
+
+    metric(
+        "proxy_cache_bytes",
+        "Total size of cached response bodies",
+        "gauge",
+        cache.bytes() as u64,
+    );
+    metric(
+        "proxy_circuit_state",
+        "Upstream circuit state (0 = closed, 1 = half-open, 2 = open)",
+        "gauge",
+        match status.state {
+            "closed" => 0,
+            "half_open" => 1,
+            _ => 2,
+        },
+    );
+    metric(
+        "proxy_circuit_consecutive_failures",
+        "Consecutive upstream failures",
+        "gauge",
+        status.consecutive_failures.into(),
+    );
+    metric(
+        "proxy_circuit_trips_total",
+        "Number of times the upstream circuit opened",
+        "counter",
+        status.trips,
+    );
+    metric(
+        "proxy_circuit_rejected_total",
+        "Upstream requests skipped because the circuit was open",
+        "counter",
+        status.rejected,
+    );
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
@@ -0,0 +1,428 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use reqwest::RequestBuilder;
+use serde_json::json;
+
+use crate::{
+    breaker::CircuitBreaker,
+    cache::{Cache, CachedResponse, cache_key, cache_ttl, keyed_headers, must_revalidate},
+    conditional::{
+        CLIENT_CONDITIONALS, is_not_modified, not_modified_headers, refresh, validators,
+    },
+    disk::DiskCache,
+    state::AppState,
+    upstream::{
+        UpstreamConfig, UpstreamError, remove_hop_by_hop, remove_per_user, send_with_retry,
+    },
+};
+
+#[derive(serde::Deserialize)]
+pub struct Data {
+    breed: String,
+    /// 0이나 음수는 역직렬화 단계에서 거부한다.
+    num_pics: Option<NonZeroU32>,
+}
+
+impl Data {
+    /// 요청 내용으로 업스트림 URL을 만든다.
+    /// 업스트림 응답을 바꾸는 값은 모두 URL에 들어가므로 캐시 키도 이 URL로 만든다.
+    /// 품종 이름은 `hound`나 `hound/afghan`처럼 `/`로 나뉜 한두 개의 구간이어야 한다.
+    /// 구간이 비었거나 소문자, 숫자, `-`가 아닌 문자를 담고 있으면 업스트림의 다른 경로를
+    /// 가리킬 수 있으므로 None을 반환한다.
+    pub fn upstream_url(&self, base_url: &str) -> Option<String> {
+        let segments: Vec<&str> = self.breed.split('/').collect();
+        let valid = segments.len() <= 2
+            && segments.iter().all(|segment| {
+                !segment.is_empty()
+                    && segment
+                        .bytes()
+                        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            });
+        if !valid {
+            return None;
+        }
+
+        Some(format!(
+            "{}/api/breed/{}/images/random{}",
+            base_url.trim_end_matches('/'),
+            &self.breed,
+            &self
+                .num_pics
+                .map(|num| format!("/{}", num))
+                .unwrap_or_default()
+        ))
+    }
+}
+
+/// 업스트림 요청 결과
+pub type FetchResult = Result<CachedResponse, UpstreamError>;
+
+/// 캐시를 거쳐 받은 응답
+pub enum Cached {
+    /// 캐시에 있었거나 업스트림에서 새로 받은 응답
+    Fresh(CachedResponse),
+    /// 서킷이 열려 있어서 대신 돌려주는 만료된 응답
+    Stale(CachedResponse),
+}
+
+impl IntoResponse for Cached {
+    fn into_response(self) -> Response {
+        match self {
+            Cached::Fresh(response) => {
+                (response.status, response.headers, response.body).into_response()
+            }
+            Cached::Stale(response) => (
+                response.status,
+                response.headers,
+                [(header::WARNING, r#"110 - "Response is Stale""#)],
+                response.body,
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// 캐시와 업스트림 요청에 필요한 상태를 모두 쓰므로 `AppState`를 통째로 받는다.
+pub async fn proxy_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(data): Json<Data>,
+) -> Result<Response, UpstreamError> {
+    let Some(url) = data.upstream_url(&state.upstream.base_url) else {
+        return Ok((StatusCode::BAD_REQUEST, Json(json!("Invalid breed"))).into_response());
+    };
+    let vary_headers = &state.cache.config().vary_headers;
+    let key = cache_key("GET", &url, &headers, vary_headers);
+
+    // 캐시 키에 들어간 헤더만 업스트림으로 전달한다. 조건부 요청은 프록시가 직접 답한다.
+    let mut forwarded = keyed_headers(&headers, vary_headers);
+    for name in &CLIENT_CONDITIONALS {
+        forwarded.remove(name);
+    }
+    let request = state.client.get(url).headers(forwarded);
+
+    let cached = cached_get(&state, key, request).await?;
+    let (Cached::Fresh(response) | Cached::Stale(response)) = &cached;
+    if is_not_modified(&headers, response) {
+        return Ok((StatusCode::NOT_MODIFIED, not_modified_headers(response)).into_response());
+    }
+    Ok(cached.into_response())
+}
+
+/// GET 요청을 메모리 캐시, 디스크 캐시, 업스트림 순서로 찾는다.
+/// 같은 키로 동시에 들어온 캐시 미스는 업스트림 요청 하나를 함께 기다린다.
+/// 만료된 항목이 남아 있으면 업스트림에 바뀌었는지 확인하고,
+/// 서킷이 열려 있으면 그 항목을 대신 돌려준다. 다만 `no-cache`처럼 확인을 요구한 항목은 돌려주지 않는다.
+pub async fn cached_get(
+    state: &AppState,
+    key: String,
+    request: RequestBuilder,
+) -> Result<Cached, UpstreamError> {
+    let AppState {
+        cache,
+        disk,
+        flights,
+        upstream: config,
+        breaker,
+        ..
+    } = state;
+
+    if let Some(cached) = cache.get(&key) {
+        tracing::debug!("{key} 캐시 히트");
+        return Ok(Cached::Fresh(cached));
+    }
+
+    // 디스크에 있으면 메모리 캐시로 올린다.
+    if let Some(disk) = disk
+        && let Some((cached, ttl)) = disk.get(&key, Duration::ZERO).await
+    {
+        tracing::debug!("{key} 디스크 캐시 히트");
+        cache.insert(key, cached.clone(), ttl);
+        return Ok(Cached::Fresh(cached));
+    }
+
+    tracing::debug!("{key} 캐시 미스");
+
+    let stale = get_stale(cache, disk.as_ref(), &key).await;
+    let fetch = {
+        let breaker = breaker.clone();
+        let cache = cache.clone();
+        let disk = disk.clone();
+        let config = config.clone();
+        let key = key.clone();
+        let stale = stale.clone();
+        move || fetch_upstream(breaker, cache, disk, config, key, request, stale)
+    };
+
+    match (flights.run(&key, config.timeout, fetch).await?, stale) {
+        (Ok(response), _) => Ok(Cached::Fresh(response)),
+        (Err(UpstreamError::CircuitOpen(_)), Some(stale)) if !must_revalidate(&stale.headers) => {
+            tracing::warn!("{key} 서킷 열림, 만료된 캐시 응답");
+            Ok(Cached::Stale(stale))
+        }
+        (Err(err), _) => Err(err),
+    }
+}
+
+/// 메모리 캐시와 디스크 캐시에서 차례로 만료된 항목까지 찾는다.
+async fn get_stale(cache: &Cache, disk: Option<&DiskCache>, key: &str) -> Option<CachedResponse> {
+    if let Some(stale) = cache.get_stale(key) {
+        return Some(stale);
+    }
+    let (stale, _) = disk?.get(key, cache.config().max_stale).await?;
+    Some(stale)
+}
+
+/// 업스트림 응답을 캐시할 기간을 정한다.
+/// 성공한 응답은 응답 헤더를 따르고, 404와 410은 `negative_ttl`이 있을 때만 그 기간 동안 캐시한다.
+/// 그 밖의 응답은 캐시하지 않는다.
+fn response_ttl(
+    status: StatusCode,
+    headers: &HeaderMap,
+    cache: &Cache,
+    config: &UpstreamConfig,
+) -> Option<Duration> {
+    if status.is_success() {
+        cache_ttl(headers, cache.config().default_ttl)
+    } else if matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+        config.negative_ttl
+    } else {
+        None
+    }
+}
+
+/// 업스트림에 요청하고 캐시할 수 있는 응답이면 메모리 캐시와 디스크 캐시에 저장한다.
+/// 만료된 항목이 있으면 조건부 요청으로 보내고, 304를 받으면 그 항목의 본문을 다시 쓴다.
+async fn fetch_upstream(
+    breaker: CircuitBreaker,
+    cache: Cache,
+    disk: Option<DiskCache>,
+    config: UpstreamConfig,
+    key: String,
+    request: RequestBuilder,
+    stale: Option<CachedResponse>,
+) -> FetchResult {
+    let permit = breaker.acquire().map_err(UpstreamError::CircuitOpen)?;
+
+    let validators = stale.as_ref().map(validators).unwrap_or_default();
+    if validators.is_empty() {
+        tracing::debug!("{key} 업스트림 요청");
+    } else {
+        tracing::debug!("{key} 업스트림에 변경 여부 확인");
+    }
+    let request = request.headers(validators);
+
+    let result = send_with_retry(&config, &Method::GET, || {
+        request
+            .try_clone()
+            .expect("GET request has no streaming body")
+    })
+    .await;
+
+    // 업스트림 자체의 문제만 실패로 센다. 404 같은 응답은 업스트림이 정상이라는 뜻이다.
+    let res = match result {
+        Ok(res) if !res.status.is_server_error() => {
+            permit.success();
+            res
+        }
+        Ok(res) => {
+            permit.failure();
+            res
+        }
+        Err(err) => {
+            permit.failure();
+            return Err(err);
+        }
+    };
+
+    let mut response = match stale {
+        Some(stale) if res.status == StatusCode::NOT_MODIFIED => {
+            tracing::debug!("{key} 변경 없음, 저장한 본문 재사용");
+            refresh(stale, &res.headers)
+        }
+        _ => {
+            let mut headers = res.headers;
+            remove_hop_by_hop(&mut headers);
+            CachedResponse {
+                status: res.status,
+                headers,
+                body: res.body,
+            }
+        }
+    };
+
+    // 이 응답은 캐시에 저장되고 같은 키를 기다리던 다른 요청에도 그대로 돌아가므로,
+    // 한 클라이언트에게 보낸 쿠키가 다른 클라이언트에게 가지 않도록 지운다.
+    if remove_per_user(&mut response.headers) {
+        tracing::debug!("{key} 응답의 Set-Cookie 헤더를 지움");
+    }
+
+    if let Some(ttl) = response_ttl(response.status, &response.headers, &cache, &config) {
+        // 디스크에 저장하지 못해도 응답은 그대로 돌려준다.
+        if let Some(disk) = &disk
+            && let Err(err) = disk.insert(key.clone(), &response, ttl).await
+        {
+            tracing::warn!("{key} 디스크 캐시 저장 실패: {err}");
+        }
+        cache.insert(key, response.clone(), ttl);
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Bytes,
+        http::{HeaderName, HeaderValue},
+    };
+
+    use super::*;
+    use crate::cache::CacheConfig;
+
+    fn data(breed: &str, num_pics: Option<u32>) -> Data {
+        Data {
+            breed: breed.to_string(),
+            num_pics: num_pics.map(|num| NonZeroU32::new(num).unwrap()),
+        }
+    }
+
+    fn key(data: &Data, headers: &HeaderMap, vary_headers: &[HeaderName]) -> String {
+        cache_key(
+            "GET",
+            &data.upstream_url("https://dog.ceo").unwrap(),
+            headers,
+            vary_headers,
+        )
+    }
+
+    #[test]
+    fn every_parameter_changes_the_key() {
+        let headers = HeaderMap::new();
+        let keys = [
+            key(&data("hound", None), &headers, &[]),
+            key(&data("hound", Some(1)), &headers, &[]),
+            key(&data("hound", Some(5)), &headers, &[]),
+            key(&data("husky", None), &headers, &[]),
+            key(&data("husky", Some(5)), &headers, &[]),
+        ];
+
+        for (i, a) in keys.iter().enumerate() {
+            for b in &keys[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn breed_outside_the_allowed_characters_is_rejected() {
+        for breed in [
+            "",
+            "hound/",
+            "/afghan",
+            "hound//afghan",
+            "hound/afghan/x",
+            "../admin",
+            "hound?x=1",
+            "Hound",
+            "hound%2F",
+            "불독",
+        ] {
+            assert!(
+                data(breed, None).upstream_url("https://dog.ceo").is_none(),
+                "{breed:?}"
+            );
+        }
+        assert_eq!(
+            data("shiba-inu2", Some(3)).upstream_url("https://dog.ceo/"),
+            Some("https://dog.ceo/api/breed/shiba-inu2/images/random/3".to_string())
+        );
+        assert_eq!(
+            data("hound/afghan", None).upstream_url("https://dog.ceo"),
+            Some("https://dog.ceo/api/breed/hound/afghan/images/random".to_string())
+        );
+    }
+
+    #[test]
+    fn same_parameters_share_the_key() {
+        let headers = HeaderMap::new();
+        assert_eq!(
+            key(&data("hound", Some(5)), &headers, &[]),
+            key(&data("hound", Some(5)), &headers, &[]),
+        );
+    }
+
+    #[test]
+    fn vary_headers_change_the_key() {
+        let vary = [header::ACCEPT_LANGUAGE];
+        let data = data("hound", None);
+
+        let mut korean = HeaderMap::new();
+        korean.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("ko"));
+        let mut english = HeaderMap::new();
+        english.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("en"));
+        let mut empty = HeaderMap::new();
+        empty.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static(""));
+        let missing = HeaderMap::new();
+
+        let keys = [
+            key(&data, &korean, &vary),
+            key(&data, &english, &vary),
+            key(&data, &empty, &vary),
+            key(&data, &missing, &vary),
+        ];
+        for (i, a) in keys.iter().enumerate() {
+            for b in &keys[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        // 지정하지 않은 헤더는 키에 영향을 주지 않는다.
+        assert_eq!(key(&data, &korean, &[]), key(&data, &english, &[]));
+    }
+
+    #[test]
+    fn vary_header_order_does_not_matter() {
+        let data = data("hound", None);
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("ko"));
+
+        assert_eq!(
+            key(&data, &headers, &[header::ACCEPT, header::ACCEPT_LANGUAGE]),
+            key(&data, &headers, &[header::ACCEPT_LANGUAGE, header::ACCEPT]),
+        );
+    }
+
+    #[test]
+    fn different_parameters_are_cached_separately() {
+        let cache = Cache::new(CacheConfig {
+            default_ttl: Duration::from_secs(60),
+            max_bytes: 1024,
+            max_stale: Duration::ZERO,
+            vary_headers: Vec::new(),
+        });
+        let headers = HeaderMap::new();
+        let one = key(&data("hound", None), &headers, &[]);
+        let five = key(&data("hound", Some(5)), &headers, &[]);
+
+        cache.insert(
+            one.clone(),
+            CachedResponse {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(b"one"),
+            },
+            Duration::from_secs(60),
+        );
+
+        assert!(cache.get(&five).is_none());
+        assert_eq!(cache.get(&one).unwrap().body, Bytes::from_static(b"one"));
+    }
+}
@@ -0,0 +1,30 @@
+//! 이 앱의 요청 수 제한 설정
+//!
+//! 토큰 버킷과 미들웨어는 `rate-limiter` 크레이트에 있고, 여기서는 어떤 경로에 어떤 제한을 걸지만 정한다.
+
+use std::sync::Arc;
+
+pub use rate_limiter::{
+    AuthenticatedUser, BoxFuture, Decision, KeyBy, MemoryStore, Quota, RateLimitStore, RateLimiter,
+    rate_limit,
+};
+
+/// 경로별 요청 수 제한. 설정하지 않은 경로는 제한하지 않는다.
+#[derive(Clone, Default)]
+pub struct RateLimits {
+    /// 프록시 요청과 리버스 프록시 요청
+    pub proxy: Option<RateLimiter>,
+    /// 관리 API와 메트릭
+    pub admin: Option<RateLimiter>,
+}
+
+impl RateLimits {
+    /// `PROXY_RATE_LIMIT_*`과 `PROXY_ADMIN_RATE_LIMIT_*` 환경 변수에서 설정을 읽는다.
+    /// 두 경로가 저장소 하나를 함께 쓰고, 버킷은 경로 이름으로 나눈다.
+    pub fn from_env(store: Arc<dyn RateLimitStore>) -> Self {
+        RateLimits {
+            proxy: RateLimiter::from_env("proxy", "PROXY_RATE_LIMIT", store.clone()),
+            admin: RateLimiter::from_env("admin", "PROXY_ADMIN_RATE_LIMIT", store),
+        }
+    }
+}
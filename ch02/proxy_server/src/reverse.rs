@@ -0,0 +1,233 @@
+//! 리버스 프록시 모드
+//!
+//! 설정한 경로 접두사로 들어온 요청을 그 접두사에 연결된 업스트림으로 그대로 전달한다.
+//! `/api=http://localhost:9000`이면 `GET /api/users?page=2`를 `GET http://localhost:9000/users?page=2`로 보낸다.
+//!
+//! - GET 요청은 캐시를 거친다. 캐시하려고 업스트림 응답 본문을 모두 받은 뒤 돌려준다.
+//!   업스트림에는 캐시 키에 들어간 `vary_headers`만 보낸다.
+//!   클라이언트의 조건부 요청은 업스트림으로 보내지 않고 캐시한 응답으로 답한다.
+//!   캐시를 거친 응답은 여러 클라이언트가 함께 쓰므로 `Set-Cookie` 헤더를 지운다.
+//! - 그 밖의 요청과 `Authorization`이나 `Cookie` 헤더가 있는 GET 요청은 캐시하지 않고,
+//!   본문을 양방향으로 스트리밍한다.
+
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::{
+    cache::{cache_key, keyed_headers},
+    conditional::{CLIENT_CONDITIONALS, is_not_modified, not_modified_headers},
+    proxy::{Cached, cached_get},
+    state::AppState,
+    upstream::{UpstreamError, remove_hop_by_hop},
+};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// 경로 접두사와 업스트림 주소의 연결
+#[derive(Clone)]
+pub struct Route {
+    /// `/`로 시작하는 경로 접두사
+    pub prefix: String,
+    /// 업스트림 주소. 접두사를 뺀 나머지 경로를 이 뒤에 붙인다.
+    pub upstream: String,
+}
+
+/// 리버스 프록시 경로 설정
+#[derive(Clone, Default)]
+pub struct Routes {
+    /// 접두사가 긴 것부터 정렬해서 가장 구체적인 경로를 먼저 찾는다.
+    routes: Vec<Route>,
+}
+
+impl Routes {
+    pub fn new(mut routes: Vec<Route>) -> Self {
+        for route in &mut routes {
+            route.prefix = route.prefix.trim_end_matches('/').to_string();
+            route.upstream = route.upstream.trim_end_matches('/').to_string();
+        }
+        routes.sort_by_key(|route| std::cmp::Reverse(route.prefix.len()));
+        Routes { routes }
+    }
+
+    /// `/api=http://localhost:9000,/img=http://localhost:9001` 형식의 설정을 읽는다.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let routes = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|route| !route.is_empty())
+            .map(|route| {
+                let (prefix, upstream) = route
+                    .split_once('=')
+                    .ok_or_else(|| format!("Route must be PREFIX=UPSTREAM: {route}"))?;
+                if !prefix.starts_with('/') {
+                    return Err(format!("Route prefix must start with '/': {prefix}"));
+                }
+                Ok(Route {
+                    prefix: prefix.to_string(),
+                    upstream: upstream.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Routes::new(routes))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// 경로에 맞는 업스트림 URL을 만든다.
+    /// 접두사는 경로 구분자 단위로 비교하므로 `/api`는 `/apis`와 맞지 않는다.
+    fn upstream_url(&self, path: &str, query: Option<&str>) -> Option<String> {
+        let (route, rest) = self.routes.iter().find_map(|route| {
+            let rest = path.strip_prefix(&route.prefix)?;
+            (rest.is_empty() || rest.starts_with('/')).then_some((route, rest))
+        })?;
+
+        let mut url = format!("{}{rest}", route.upstream);
+        if let Some(query) = query {
+            url.push('?');
+            url.push_str(query);
+        }
+        Some(url)
+    }
+}
+
+/// 업스트림으로 보낼 요청 헤더를 만든다.
+/// 홉 단위 헤더와 `Host`는 빼고 `X-Forwarded-*` 헤더를 더한다.
+fn forwarded_headers(incoming: &HeaderMap, client: Option<SocketAddr>) -> HeaderMap {
+    let mut headers = incoming.clone();
+    remove_hop_by_hop(&mut headers);
+    let host = headers.remove(header::HOST);
+
+    // 앞에 다른 프록시가 있었으면 그 목록 뒤에 클라이언트 주소를 붙인다.
+    if let Some(client) = client {
+        let forwarded_for = match headers
+            .get(X_FORWARDED_FOR)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(previous) => format!("{previous}, {}", client.ip()),
+            None => client.ip().to_string(),
+        };
+        if let Ok(value) = HeaderValue::try_from(forwarded_for) {
+            headers.insert(X_FORWARDED_FOR, value);
+        }
+    }
+    if let Some(host) = host
+        && !headers.contains_key(X_FORWARDED_HOST)
+    {
+        headers.insert(X_FORWARDED_HOST, host);
+    }
+    if !headers.contains_key(X_FORWARDED_PROTO) {
+        headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static("http"));
+    }
+
+    headers
+}
+
+/// 리버스 프록시 핸들러
+/// 다른 경로에 맞지 않은 요청을 모두 받는다.
+pub async fn reverse_proxy(
+    State(state): State<AppState>,
+    request: Request,
+) -> Result<Response, UpstreamError> {
+    let Some(url) = state
+        .routes
+        .upstream_url(request.uri().path(), request.uri().query())
+    else {
+        return Ok((StatusCode::NOT_FOUND, Json(json!("No route for path"))).into_response());
+    };
+
+    // 서버를 `into_make_service_with_connect_info`로 띄웠을 때만 클라이언트 주소를 안다.
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    let (parts, body) = request.into_parts();
+    let headers = forwarded_headers(&parts.headers, client);
+
+    // 사용자마다 응답이 다를 수 있는 요청은 공유 캐시에 넣지 않는다.
+    let personal =
+        headers.contains_key(header::AUTHORIZATION) || headers.contains_key(header::COOKIE);
+    if parts.method == Method::GET && !personal {
+        let vary_headers = &state.cache.config().vary_headers;
+        let key = cache_key("GET", &url, &parts.headers, vary_headers);
+        // 캐시 키에 들어간 헤더만 보낸다. `Accept-Encoding`이나 `Accept-Language`처럼 키에 없는 헤더로
+        // 압축하거나 협상한 응답이 저장되어 다른 클라이언트에게 가지 않게 한다.
+        let mut headers = keyed_headers(&parts.headers, vary_headers);
+        for name in &CLIENT_CONDITIONALS {
+            headers.remove(name);
+        }
+        let request = state.client.get(&url).headers(headers);
+
+        let cached = cached_get(&state, key, request).await?;
+        let (Cached::Fresh(response) | Cached::Stale(response)) = &cached;
+        if is_not_modified(&parts.headers, response) {
+            return Ok((StatusCode::NOT_MODIFIED, not_modified_headers(response)).into_response());
+        }
+        return Ok(cached.into_response());
+    }
+
+    stream_upstream(&state, parts.method, url, headers, body).await
+}
+
+/// 요청 본문을 업스트림으로 흘려보내고 응답 본문도 받는 대로 돌려준다.
+/// 본문을 다시 보낼 수 없으므로 재시도하지 않는다.
+async fn stream_upstream(
+    state: &AppState,
+    method: Method,
+    url: String,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, UpstreamError> {
+    let permit = state
+        .breaker
+        .acquire()
+        .map_err(UpstreamError::CircuitOpen)?;
+
+    tracing::debug!("{method} {url} 업스트림 요청");
+
+    let request = state
+        .client
+        .request(method, url)
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()));
+
+    // 응답 헤더를 받을 때까지만 기다린다. 본문은 `read_timeout`이 적용된다.
+    let res = match tokio::time::timeout(state.upstream.timeout, request.send()).await {
+        Ok(Ok(res)) => res,
+        Ok(Err(err)) => {
+            permit.failure();
+            return Err(UpstreamError::from_reqwest(err));
+        }
+        Err(_) => {
+            permit.failure();
+            return Err(UpstreamError::Timeout);
+        }
+    };
+
+    if res.status().is_server_error() {
+        permit.failure();
+    } else {
+        permit.success();
+    }
+
+    let status = res.status();
+    let mut headers = res.headers().clone();
+    remove_hop_by_hop(&mut headers);
+
+    let mut response = Response::new(Body::from_stream(res.bytes_stream()));
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    Ok(response)
+}
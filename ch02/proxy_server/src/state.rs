@@ -0,0 +1,61 @@
+use axum::extract::FromRef;
+use reqwest::Client;
+
+use crate::{
+    breaker::{BreakerConfig, CircuitBreaker},
+    cache::Cache,
+    disk::DiskCache,
+    flight::SingleFlight,
+    proxy::FetchResult,
+    ratelimit::RateLimits,
+    reverse::Routes,
+    upstream::UpstreamConfig,
+};
+
+#[derive(FromRef, Clone)]
+pub struct AppState {
+    pub cache: Cache,
+    /// 디스크 캐시. 설정하지 않으면 메모리 캐시만 쓴다.
+    pub disk: Option<DiskCache>,
+    pub flights: SingleFlight<FetchResult>,
+    pub upstream: UpstreamConfig,
+    /// 업스트림 요청에 쓰는 클라이언트. 복제해도 연결 풀을 공유한다.
+    pub client: Client,
+    pub breaker: CircuitBreaker,
+    /// 리버스 프록시 경로. 비어 있으면 리버스 프록시 모드를 쓰지 않는다.
+    pub routes: Routes,
+    pub rate_limits: RateLimits,
+}
+
+impl AppState {
+    pub fn new(cache: Cache, upstream: UpstreamConfig, breaker: BreakerConfig) -> Self {
+        AppState {
+            cache,
+            disk: None,
+            flights: SingleFlight::default(),
+            client: upstream.client(),
+            breaker: CircuitBreaker::new(breaker),
+            routes: Routes::default(),
+            rate_limits: RateLimits::default(),
+            upstream,
+        }
+    }
+
+    pub fn with_routes(self, routes: Routes) -> Self {
+        AppState { routes, ..self }
+    }
+
+    pub fn with_rate_limits(self, rate_limits: RateLimits) -> Self {
+        AppState {
+            rate_limits,
+            ..self
+        }
+    }
+
+    pub fn with_disk_cache(self, disk: DiskCache) -> Self {
+        AppState {
+            disk: Some(disk),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use axum::{
+    Json,
+    body::Bytes,
+    http::{HeaderMap, HeaderName, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use reqwest::{Client, RequestBuilder};
+use serde_json::json;
+
+use crate::flight::FlightError;
+
+/// 업스트림 설정
+#[derive(Clone)]
+pub struct UpstreamConfig {
+    /// 업스트림 주소. 끝의 `/`는 무시한다.
+    pub base_url: String,
+    /// 재시도를 포함해서 업스트림 응답을 기다리는 최대 시간
+    pub timeout: Duration,
+    /// 연결을 맺을 때까지 기다리는 최대 시간
+    pub connect_timeout: Duration,
+    /// 응답 데이터를 읽을 때 다음 데이터가 올 때까지 기다리는 최대 시간
+    pub read_timeout: Duration,
+    /// 첫 요청이 실패했을 때 다시 시도하는 최대 횟수
+    pub max_retries: u32,
+    /// 재시도 간격의 기준값. 시도할 때마다 두 배로 늘어난다.
+    pub retry_base_delay: Duration,
+    /// 404, 410 응답을 캐시하는 기간. None이면 캐시하지 않는다.
+    pub negative_ttl: Option<Duration>,
+}
+
+impl UpstreamConfig {
+    /// 이 설정의 제한 시간을 쓰는 HTTP 클라이언트를 만든다.
+    /// 클라이언트는 연결을 재사용하므로 하나만 만들어서 공유한다.
+    pub fn client(&self) -> Client {
+        Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout)
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    /// `attempt`번째 재시도 전에 기다릴 시간.
+    /// 여러 요청이 같은 순간에 다시 몰리지 않도록 0부터 상한값 사이에서 무작위로 고른다.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let max = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt));
+        max.mul_f64(fastrand::f64())
+    }
+}
+
+/// 업스트림 요청이 실패한 이유
+#[derive(Debug, Clone)]
+pub enum UpstreamError {
+    /// 제한 시간 안에 응답을 받지 못했다.
+    Timeout,
+    /// 업스트림에 연결하지 못했다.
+    Connect(String),
+    /// 요청을 보내거나 응답을 읽다가 실패했다.
+    Request(String),
+    /// 서킷이 열려 있어서 요청을 보내지 않았다. 다시 시도해도 될 때까지 남은 시간을 담는다.
+    CircuitOpen(Duration),
+}
+
+impl UpstreamError {
+    pub(crate) fn from_reqwest(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            UpstreamError::Timeout
+        } else if err.is_connect() {
+            UpstreamError::Connect(err.to_string())
+        } else {
+            UpstreamError::Request(err.to_string())
+        }
+    }
+
+    /// 같은 요청을 다시 보내면 성공할 수도 있는 오류인지 확인한다.
+    fn is_retryable(&self) -> bool {
+        matches!(self, UpstreamError::Timeout | UpstreamError::Connect(_))
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            UpstreamError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            UpstreamError::Connect(_) | UpstreamError::Request(_) => StatusCode::BAD_GATEWAY,
+            UpstreamError::CircuitOpen(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+impl From<FlightError> for UpstreamError {
+    fn from(err: FlightError) -> Self {
+        match err {
+            FlightError::Timeout => UpstreamError::Timeout,
+            FlightError::Aborted => {
+                UpstreamError::Request("Upstream request was aborted".to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for UpstreamError {
+    fn into_response(self) -> Response {
+        let message = match &self {
+            UpstreamError::Timeout => "Upstream request timed out".to_string(),
+            UpstreamError::Connect(err) => format!("Failed to connect to upstream: {err}"),
+            UpstreamError::Request(err) => format!("Upstream request failed: {err}"),
+            UpstreamError::CircuitOpen(retry_after) => {
+                // 남은 시간이 1초보다 짧아도 바로 다시 보내지 않도록 올림한다.
+                let retry_after = (retry_after.as_secs_f64().ceil() as u64).max(1);
+                return (
+                    self.status(),
+                    [(header::RETRY_AFTER, retry_after.to_string())],
+                    Json(json!("Upstream is unavailable")),
+                )
+                    .into_response();
+            }
+        };
+
+        (self.status(), Json(json!(message))).into_response()
+    }
+}
+
+/// 업스트림 응답
+pub struct UpstreamResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// 연결 하나에만 해당하는 헤더. 프록시는 이 헤더를 다음 구간으로 전달하지 않는다.
+const HOP_BY_HOP: [HeaderName; 9] = [
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+/// 홉 단위 헤더와 `Connection` 헤더에 나열된 헤더를 지운다.
+pub fn remove_hop_by_hop(headers: &mut HeaderMap) {
+    let listed = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect::<Vec<_>>();
+
+    for name in listed.iter().chain(&HOP_BY_HOP) {
+        headers.remove(name);
+    }
+}
+
+/// 응답을 받는 클라이언트에게만 해당하는 헤더.
+/// 캐시하거나 다른 클라이언트와 함께 쓰는 응답에서는 지운다.
+const PER_USER: [HeaderName; 2] = [header::SET_COOKIE, HeaderName::from_static("set-cookie2")];
+
+/// 사용자마다 다른 응답 헤더를 지운다. 지운 헤더가 있으면 true를 반환한다.
+pub fn remove_per_user(headers: &mut HeaderMap) -> bool {
+    let mut removed = false;
+    for name in &PER_USER {
+        removed |= headers.remove(name).is_some();
+    }
+    removed
+}
+
+/// 다시 보내도 결과가 같은 메서드인지 확인한다.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// 다시 시도하면 성공할 수도 있는 응답 상태인지 확인한다.
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+async fn send(request: RequestBuilder) -> Result<UpstreamResponse, UpstreamError> {
+    let res = request.send().await.map_err(UpstreamError::from_reqwest)?;
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.bytes().await.map_err(UpstreamError::from_reqwest)?;
+
+    Ok(UpstreamResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// 업스트림에 요청한다.
+/// 멱등한 요청이 연결 실패, 시간 초과, 502/503/504로 끝나면 간격을 두고 다시 시도한다.
+/// 재시도를 다 써도 실패하면 마지막 응답이나 오류를 반환한다.
+pub async fn send_with_retry(
+    config: &UpstreamConfig,
+    method: &Method,
+    make_request: impl Fn() -> RequestBuilder,
+) -> Result<UpstreamResponse, UpstreamError> {
+    let max_retries = if is_idempotent(method) {
+        config.max_retries
+    } else {
+        0
+    };
+    let mut attempt = 0;
+
+    loop {
+        let result = send(make_request()).await;
+        let retryable = match &result {
+            Ok(response) => is_retryable_status(response.status),
+            Err(err) => err.is_retryable(),
+        };
+
+        if !retryable || attempt >= max_retries {
+            return result;
+        }
+
+        let delay = config.backoff(attempt);
+        attempt += 1;
+        tracing::warn!("업스트림 요청 실패, {delay:?} 후 다시 시도 ({attempt}/{max_retries})");
+        tokio::time::sleep(delay).await;
+    }
+}
@@ -0,0 +1,65 @@
+//! 서킷 브레이커 상태 전이 테스트
+
+use std::time::Duration;
+
+use proxy_server::breaker::{BreakerConfig, CircuitBreaker};
+
+fn breaker(cool_down: Duration) -> CircuitBreaker {
+    CircuitBreaker::new(BreakerConfig {
+        failure_threshold: 2,
+        cool_down,
+    })
+}
+
+fn open(breaker: &CircuitBreaker) {
+    for _ in 0..2 {
+        breaker.acquire().unwrap().failure();
+    }
+    assert_eq!(breaker.status().state, "open");
+}
+
+#[test]
+fn slow_success_from_before_opening_keeps_circuit_open() {
+    let breaker = breaker(Duration::from_secs(60));
+    let slow = breaker.acquire().unwrap();
+    open(&breaker);
+
+    slow.success();
+    let status = breaker.status();
+    assert_eq!(status.state, "open");
+    assert!(breaker.acquire().is_err());
+}
+
+#[test]
+fn half_open_probe_decides_next_state() {
+    let breaker = breaker(Duration::from_millis(20));
+    let slow = breaker.acquire().unwrap();
+    open(&breaker);
+    std::thread::sleep(Duration::from_millis(30));
+
+    // 쉬는 시간이 지나면 시험 요청 하나만 보낸다.
+    let probe = breaker.acquire().unwrap();
+    assert_eq!(breaker.status().state, "half_open");
+    assert!(breaker.acquire().is_err());
+
+    // 열리기 전의 요청은 시험 요청의 결과를 대신하지 않는다.
+    slow.success();
+    assert_eq!(breaker.status().state, "half_open");
+
+    probe.success();
+    let status = breaker.status();
+    assert_eq!(status.state, "closed");
+    assert_eq!(status.consecutive_failures, 0);
+}
+
+#[test]
+fn failed_probe_reopens_circuit() {
+    let breaker = breaker(Duration::from_millis(20));
+    open(&breaker);
+    std::thread::sleep(Duration::from_millis(30));
+
+    drop(breaker.acquire().unwrap());
+    let status = breaker.status();
+    assert_eq!(status.state, "open");
+    assert_eq!(status.trips, 2);
+}
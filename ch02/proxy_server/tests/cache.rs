@@ -0,0 +1,226 @@
+use std::time::{Duration, SystemTime};
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+};
+use proxy_server::cache::{Cache, CacheConfig, CachedResponse, cache_ttl, must_revalidate};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+fn response(body: &'static [u8]) -> CachedResponse {
+    CachedResponse {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body: Bytes::from_static(body),
+    }
+}
+
+fn cache(max_bytes: usize, max_stale: Duration) -> Cache {
+    Cache::new(CacheConfig {
+        default_ttl: DEFAULT_TTL,
+        max_bytes,
+        max_stale,
+        vary_headers: Vec::new(),
+    })
+}
+
+fn headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(name, HeaderValue::from_str(value).unwrap());
+    }
+    headers
+}
+
+fn ttl(pairs: &[(header::HeaderName, &str)]) -> Option<Duration> {
+    cache_ttl(&headers(pairs), DEFAULT_TTL)
+}
+
+#[test]
+fn least_recently_used_entry_is_evicted_first() {
+    let cache = cache(8, Duration::ZERO);
+    cache.insert("a".to_string(), response(b"aaa"), DEFAULT_TTL);
+    cache.insert("b".to_string(), response(b"bbb"), DEFAULT_TTL);
+
+    // a를 읽어서 b가 가장 오래전에 쓴 항목이 된다.
+    assert!(cache.get("a").is_some());
+    cache.insert("c".to_string(), response(b"ccc"), DEFAULT_TTL);
+
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("c").is_some());
+    assert_eq!(cache.bytes(), 6);
+}
+
+#[test]
+fn body_larger_than_the_limit_is_not_stored() {
+    let cache = cache(4, Duration::ZERO);
+    cache.insert("a".to_string(), response(b"aa"), DEFAULT_TTL);
+    cache.insert("big".to_string(), response(b"too big"), DEFAULT_TTL);
+
+    assert!(cache.get("big").is_none());
+    assert!(cache.get("a").is_some());
+}
+
+#[test]
+fn replacing_an_entry_updates_the_size() {
+    let cache = cache(16, Duration::ZERO);
+    cache.insert("a".to_string(), response(b"aaaa"), DEFAULT_TTL);
+    cache.insert("a".to_string(), response(b"aa"), DEFAULT_TTL);
+
+    assert_eq!(cache.bytes(), 2);
+    assert_eq!(cache.entries().len(), 1);
+}
+
+#[tokio::test]
+async fn expired_entry_is_only_returned_as_stale() {
+    let cache = cache(1024, Duration::from_millis(200));
+    cache.insert("a".to_string(), response(b"a"), Duration::from_millis(50));
+    assert!(cache.get("a").is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(cache.get("a").is_none());
+    assert!(cache.get_stale("a").is_some());
+
+    // max_stale도 지나면 지운다.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(cache.get_stale("a").is_none());
+    assert_eq!(cache.bytes(), 0);
+}
+
+#[test]
+fn zero_ttl_is_kept_only_for_revalidation() {
+    let cache = cache(1024, DEFAULT_TTL);
+    cache.insert("a".to_string(), response(b"a"), Duration::ZERO);
+    assert!(cache.get("a").is_none());
+    assert!(cache.get_stale("a").is_some());
+
+    // 확인할 기간이 없으면 저장하지 않는다.
+    let cache = self::cache(1024, Duration::ZERO);
+    cache.insert("a".to_string(), response(b"a"), Duration::ZERO);
+    assert_eq!(cache.bytes(), 0);
+}
+
+#[test]
+fn max_age_sets_the_ttl() {
+    assert_eq!(
+        ttl(&[(header::CACHE_CONTROL, "public, max-age=30")]),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(
+        ttl(&[(header::CACHE_CONTROL, r#"Max-Age="30""#)]),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(ttl(&[(header::CACHE_CONTROL, "max-age=0")]), None);
+}
+
+#[test]
+fn s_maxage_wins_over_max_age() {
+    assert_eq!(
+        ttl(&[(header::CACHE_CONTROL, "max-age=30, s-maxage=120")]),
+        Some(Duration::from_secs(120))
+    );
+}
+
+#[test]
+fn cache_control_wins_over_expires() {
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, "max-age=30"),
+            (header::EXPIRES, "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ]),
+        Some(Duration::from_secs(30))
+    );
+}
+
+#[test]
+fn no_store_and_private_are_not_cached() {
+    assert_eq!(
+        ttl(&[(header::CACHE_CONTROL, "no-store, max-age=30")]),
+        None
+    );
+    assert_eq!(ttl(&[(header::CACHE_CONTROL, "private, max-age=30")]), None);
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, "no-cache, no-store"),
+            (header::ETAG, "\"v1\""),
+        ]),
+        None
+    );
+}
+
+#[test]
+fn no_cache_is_stored_for_revalidation() {
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, "no-cache, max-age=30"),
+            (header::ETAG, "\"v1\""),
+        ]),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, r#"no-cache="Set-Cookie""#),
+            (header::LAST_MODIFIED, "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ]),
+        Some(Duration::ZERO)
+    );
+    // 확인할 방법이 없으면 저장하지 않는다.
+    assert_eq!(ttl(&[(header::CACHE_CONTROL, "no-cache")]), None);
+}
+
+#[test]
+fn zero_max_age_with_validator_is_stored_for_revalidation() {
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, "max-age=0"),
+            (header::ETAG, "\"v1\""),
+        ]),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(
+        ttl(&[
+            (header::CACHE_CONTROL, "max-age=30, s-maxage=0"),
+            (header::LAST_MODIFIED, "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ]),
+        Some(Duration::ZERO)
+    );
+}
+
+#[test]
+fn no_cache_and_must_revalidate_require_revalidation() {
+    assert!(must_revalidate(&headers(&[(
+        header::CACHE_CONTROL,
+        "no-cache"
+    )])));
+    assert!(must_revalidate(&headers(&[(
+        header::CACHE_CONTROL,
+        "max-age=30, must-revalidate"
+    )])));
+    assert!(!must_revalidate(&headers(&[(
+        header::CACHE_CONTROL,
+        "max-age=30"
+    )])));
+    assert!(!must_revalidate(&HeaderMap::new()));
+}
+
+#[test]
+fn expires_sets_the_ttl() {
+    let expires = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+    let ttl = ttl(&[(header::EXPIRES, &expires)]).unwrap();
+    assert!(ttl > Duration::from_secs(110) && ttl <= Duration::from_secs(120));
+
+    assert_eq!(
+        self::ttl(&[(header::EXPIRES, "Thu, 01 Jan 1970 00:00:00 GMT")]),
+        None
+    );
+    // 형식이 잘못된 Expires는 이미 만료된 것으로 본다.
+    assert_eq!(self::ttl(&[(header::EXPIRES, "0")]), None);
+}
+
+#[test]
+fn default_ttl_is_used_without_freshness_headers() {
+    assert_eq!(ttl(&[]), Some(DEFAULT_TTL));
+    assert_eq!(ttl(&[(header::CACHE_CONTROL, "public")]), Some(DEFAULT_TTL));
+}
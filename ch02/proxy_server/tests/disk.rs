@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
+};
+use proxy_server::{
+    cache::{Cache, CacheConfig, CachedResponse},
+    disk::{DiskCache, DiskCacheConfig},
+};
+use tempfile::TempDir;
+
+fn response(body: &'static [u8]) -> CachedResponse {
+    CachedResponse {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body: Bytes::from_static(body),
+    }
+}
+
+async fn open(dir: &TempDir, max_bytes: u64) -> DiskCache {
+    open_with(dir, max_bytes, Duration::ZERO).await
+}
+
+async fn open_with(dir: &TempDir, max_bytes: u64, max_stale: Duration) -> DiskCache {
+    DiskCache::open(DiskCacheConfig {
+        dir: dir.path().to_path_buf(),
+        max_bytes,
+        max_stale,
+    })
+    .await
+    .unwrap()
+}
+
+fn objects(dir: &TempDir) -> usize {
+    std::fs::read_dir(dir.path().join("objects"))
+        .unwrap()
+        .count()
+}
+
+const TTL: Duration = Duration::from_secs(60);
+
+#[tokio::test]
+async fn same_body_is_stored_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 1024).await;
+
+    disk.insert("a".to_string(), &response(b"same"), TTL)
+        .await
+        .unwrap();
+    disk.insert("b".to_string(), &response(b"same"), TTL)
+        .await
+        .unwrap();
+
+    assert_eq!(objects(&dir), 1);
+    assert_eq!(disk.bytes().await, 4);
+
+    // 다른 키가 아직 쓰는 본문 파일은 남긴다.
+    disk.remove("a").await.unwrap();
+    assert_eq!(objects(&dir), 1);
+    disk.remove("b").await.unwrap();
+    assert_eq!(objects(&dir), 0);
+}
+
+#[tokio::test]
+async fn least_recently_used_entry_is_evicted_over_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 10).await;
+
+    disk.insert("a".to_string(), &response(b"aaaa"), TTL)
+        .await
+        .unwrap();
+    disk.insert("b".to_string(), &response(b"bbbb"), TTL)
+        .await
+        .unwrap();
+    assert!(disk.get("a", Duration::ZERO).await.is_some());
+    disk.insert("c".to_string(), &response(b"cccc"), TTL)
+        .await
+        .unwrap();
+
+    assert!(disk.get("a", Duration::ZERO).await.is_some());
+    assert!(disk.get("b", Duration::ZERO).await.is_none());
+    assert!(disk.get("c", Duration::ZERO).await.is_some());
+    assert_eq!(disk.bytes().await, 8);
+    assert_eq!(objects(&dir), 2);
+}
+
+#[tokio::test]
+async fn shared_body_is_freed_with_its_last_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 8).await;
+
+    disk.insert("a".to_string(), &response(b"same"), TTL)
+        .await
+        .unwrap();
+    disk.insert("b".to_string(), &response(b"same"), TTL)
+        .await
+        .unwrap();
+    disk.insert("c".to_string(), &response(b"cccc"), TTL)
+        .await
+        .unwrap();
+    // a를 지워도 b가 본문을 쓰므로 b까지 지워야 자리가 난다.
+    disk.insert("d".to_string(), &response(b"dddd"), TTL)
+        .await
+        .unwrap();
+
+    assert!(disk.get("a", Duration::ZERO).await.is_none());
+    assert!(disk.get("b", Duration::ZERO).await.is_none());
+    assert!(disk.get("c", Duration::ZERO).await.is_some());
+    assert!(disk.get("d", Duration::ZERO).await.is_some());
+    assert_eq!(disk.bytes().await, 8);
+    assert_eq!(objects(&dir), 2);
+}
+
+#[tokio::test]
+async fn entries_past_max_stale_are_evicted() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open_with(&dir, 1024, TTL).await;
+    disk.insert("expired".to_string(), &response(b"old"), Duration::ZERO)
+        .await
+        .unwrap();
+    disk.insert("fresh".to_string(), &response(b"new"), TTL)
+        .await
+        .unwrap();
+    assert!(disk.get("expired", TTL).await.is_some());
+    drop(disk);
+
+    // 자리가 남아도 max_stale이 지난 항목은 지운다.
+    let disk = open(&dir, 1024).await;
+    assert!(disk.get("expired", TTL).await.is_none());
+    assert!(disk.get("fresh", Duration::ZERO).await.is_some());
+    assert_eq!(disk.bytes().await, 3);
+    assert_eq!(objects(&dir), 1);
+}
+
+#[tokio::test]
+async fn entries_survive_reopen_within_new_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 1024).await;
+    disk.insert("a".to_string(), &response(b"aaaa"), TTL)
+        .await
+        .unwrap();
+    disk.insert("b".to_string(), &response(b"bbbb"), TTL)
+        .await
+        .unwrap();
+    drop(disk);
+
+    // 제한을 줄여서 다시 열면 오래된 항목부터 지운다.
+    let disk = open(&dir, 4).await;
+    assert!(disk.get("a", Duration::ZERO).await.is_none());
+    let (cached, ttl) = disk.get("b", Duration::ZERO).await.unwrap();
+    assert_eq!(cached.body, Bytes::from_static(b"bbbb"));
+    assert!(ttl <= TTL && !ttl.is_zero());
+    assert_eq!(objects(&dir), 1);
+}
+
+#[tokio::test]
+async fn missing_object_and_orphan_files_are_cleaned_up() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 1024).await;
+    disk.insert("a".to_string(), &response(b"aaaa"), TTL)
+        .await
+        .unwrap();
+    drop(disk);
+
+    for file in std::fs::read_dir(dir.path().join("objects")).unwrap() {
+        std::fs::remove_file(file.unwrap().path()).unwrap();
+    }
+    std::fs::write(dir.path().join("objects").join("orphan"), b"x").unwrap();
+
+    let disk = open(&dir, 1024).await;
+    assert!(disk.get("a", Duration::ZERO).await.is_none());
+    assert_eq!(disk.bytes().await, 0);
+    assert_eq!(objects(&dir), 0);
+}
+
+#[tokio::test]
+async fn warm_fills_memory_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 1024).await;
+    disk.insert("a".to_string(), &response(b"aaaa"), TTL)
+        .await
+        .unwrap();
+    disk.insert("b".to_string(), &response(b"bbbb"), TTL)
+        .await
+        .unwrap();
+
+    let cache = Cache::new(CacheConfig {
+        default_ttl: TTL,
+        max_bytes: 1024,
+        max_stale: Duration::ZERO,
+        vary_headers: Vec::new(),
+    });
+
+    assert_eq!(disk.warm(&cache).await, 2);
+    assert_eq!(cache.get("a").unwrap().body, Bytes::from_static(b"aaaa"));
+    assert_eq!(cache.get("b").unwrap().body, Bytes::from_static(b"bbbb"));
+}
+
+#[tokio::test]
+async fn response_headers_are_stored() {
+    let dir = tempfile::tempdir().unwrap();
+    let disk = open(&dir, 1024).await;
+    let mut cached = response(b"{}");
+    cached.headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "application/json".parse().unwrap(),
+    );
+    disk.insert("a".to_string(), &cached, TTL).await.unwrap();
+    drop(disk);
+
+    let disk = open(&dir, 1024).await;
+    let (cached, _) = disk.get("a", Duration::ZERO).await.unwrap();
+    assert_eq!(cached.headers["content-type"], "application/json");
+}
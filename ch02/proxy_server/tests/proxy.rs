@@ -0,0 +1,640 @@
+//! 로컬 스텁 업스트림을 띄우고 그 앞에서 프록시를 실행하는 통합 테스트
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use proxy_server::{
+    breaker::BreakerConfig,
+    cache::{Cache, CacheConfig},
+    disk::{DiskCache, DiskCacheConfig},
+    router,
+    state::AppState,
+    upstream::UpstreamConfig,
+};
+use serde_json::{Value, json};
+
+/// 품종별로 받은 요청 수를 세는 스텁 업스트림
+#[derive(Clone, Default)]
+struct Stub {
+    hits: Arc<Mutex<HashMap<String, usize>>>,
+    /// true면 모든 요청에 503으로 응답한다.
+    down: Arc<AtomicBool>,
+}
+
+impl Stub {
+    fn set_down(&self, down: bool) {
+        self.down.store(down, Ordering::SeqCst);
+    }
+
+    fn hits(&self, breed: &str) -> usize {
+        self.hits.lock().unwrap().get(breed).copied().unwrap_or(0)
+    }
+}
+
+async fn random_image(
+    State(stub): State<Stub>,
+    Path(breed): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    stub_response(stub, breed, 1, headers).await
+}
+
+async fn random_images(
+    State(stub): State<Stub>,
+    Path((breed, num)): Path<(String, usize)>,
+    headers: HeaderMap,
+) -> Response {
+    stub_response(stub, breed, num, headers).await
+}
+
+async fn sub_breed_image(
+    State(stub): State<Stub>,
+    Path((breed, sub)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    stub_response(stub, format!("{breed}/{sub}"), 1, headers).await
+}
+
+/// 품종 이름에 따라 다르게 응답한다.
+async fn stub_response(stub: Stub, breed: String, num: usize, headers: HeaderMap) -> Response {
+    let hits = {
+        let mut hits = stub.hits.lock().unwrap();
+        let count = hits.entry(breed.clone()).or_default();
+        *count += 1;
+        *count
+    };
+
+    let images = (0..num)
+        .map(|i| format!("https://images.test/{breed}/{hits}-{i}.jpg"))
+        .collect::<Vec<_>>();
+    let ok = Json(json!({ "message": images, "status": "success" }));
+
+    if stub.down.load(Ordering::SeqCst) {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+
+    match breed.as_str() {
+        "unknown" => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "Breed not found", "status": "error" })),
+        )
+            .into_response(),
+        // 첫 요청만 실패한다.
+        "flaky" if hits == 1 => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+        "down" => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+        "slow" => {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            ok.into_response()
+        }
+        "sleepy" => {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            ok.into_response()
+        }
+        "private" => ([(header::CACHE_CONTROL, "no-store")], ok).into_response(),
+        "brief" => ([(header::CACHE_CONTROL, "max-age=1")], ok).into_response(),
+        // 저장은 허용하지만 쓸 때마다 확인하게 한다. ETag가 같으면 304로 답한다.
+        "revalidate" if headers.contains_key(header::IF_NONE_MATCH) => {
+            StatusCode::NOT_MODIFIED.into_response()
+        }
+        "revalidate" => (
+            [
+                (header::CACHE_CONTROL, "no-cache"),
+                (header::ETAG, "\"v1\""),
+            ],
+            ok,
+        )
+            .into_response(),
+        _ => ok.into_response(),
+    }
+}
+
+async fn serve(app: Router) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+async fn spawn_stub() -> (Stub, String) {
+    let stub = Stub::default();
+    let app = Router::new()
+        .route("/api/breed/{breed}/images/random", get(random_image))
+        .route("/api/breed/{breed}/images/random/{num}", get(random_images))
+        .route(
+            "/api/breed/{breed}/{sub}/images/random",
+            get(sub_breed_image),
+        )
+        .with_state(stub.clone());
+    let addr = serve(app).await;
+    (stub, format!("http://{addr}"))
+}
+
+fn upstream_config(base_url: String) -> UpstreamConfig {
+    UpstreamConfig {
+        base_url,
+        timeout: Duration::from_millis(500),
+        connect_timeout: Duration::from_millis(200),
+        read_timeout: Duration::from_secs(1),
+        max_retries: 2,
+        retry_base_delay: Duration::from_millis(10),
+        negative_ttl: None,
+    }
+}
+
+fn breaker_config() -> BreakerConfig {
+    BreakerConfig {
+        failure_threshold: 5,
+        cool_down: Duration::from_secs(30),
+    }
+}
+
+/// 프록시를 띄우고 주소를 반환한다.
+async fn spawn_proxy(upstream: UpstreamConfig) -> String {
+    spawn_proxy_with(upstream, breaker_config()).await
+}
+
+async fn spawn_proxy_with(upstream: UpstreamConfig, breaker: BreakerConfig) -> String {
+    serve_proxy(AppState::new(memory_cache(), upstream, breaker)).await
+}
+
+async fn serve_proxy(state: AppState) -> String {
+    let addr = serve(router(state)).await;
+    format!("http://{addr}")
+}
+
+fn memory_cache() -> Cache {
+    Cache::new(CacheConfig {
+        default_ttl: Duration::from_secs(60),
+        max_bytes: 1024 * 1024,
+        max_stale: Duration::from_secs(60),
+        vary_headers: Vec::new(),
+    })
+}
+
+async fn fetch(proxy: &str, body: Value) -> (StatusCode, Value) {
+    let res = reqwest::Client::new()
+        .post(format!("{proxy}/"))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    let status = res.status();
+    (status, res.json().await.unwrap_or(Value::Null))
+}
+
+async fn breaker_status(proxy: &str) -> Value {
+    reqwest::get(format!("{proxy}/admin/breaker"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn cache_entries(proxy: &str) -> Vec<Value> {
+    let cache = reqwest::get(format!("{proxy}/admin/cache"))
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    cache["entries"].as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn successful_response_is_served_from_cache() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (status, first) = fetch(&proxy, json!({ "breed": "hound" })).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, second) = fetch(&proxy, json!({ "breed": "hound" })).await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(first, second);
+    assert_eq!(stub.hits("hound"), 1);
+    assert_eq!(cache_entries(&proxy).await.len(), 1);
+}
+
+#[tokio::test]
+async fn invalid_breed_is_rejected_without_upstream_request() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (status, body) = fetch(&proxy, json!({ "breed": "../../admin" })).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body, json!("Invalid breed"));
+    assert_eq!(stub.hits("admin"), 0);
+    assert!(cache_entries(&proxy).await.is_empty());
+}
+
+#[tokio::test]
+async fn sub_breed_is_forwarded() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (status, body) = fetch(&proxy, json!({ "breed": "hound/afghan" })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["message"].as_array().unwrap().len(), 1);
+    assert_eq!(stub.hits("hound/afghan"), 1);
+}
+
+#[tokio::test]
+async fn non_positive_num_pics_is_rejected_without_upstream_request() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    for num_pics in [0, -1] {
+        let (status, _) = fetch(&proxy, json!({ "breed": "husky", "num_pics": num_pics })).await;
+        assert!(status.is_client_error(), "{num_pics}: {status}");
+    }
+    assert_eq!(stub.hits("husky"), 0);
+}
+
+#[tokio::test]
+async fn upstream_headers_are_returned() {
+    let (_, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{proxy}/"))
+        .json(&json!({ "breed": "revalidate" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.headers()[header::ETAG], "\"v1\"");
+    assert_eq!(res.headers()[header::CACHE_CONTROL], "no-cache");
+}
+
+#[tokio::test]
+async fn matching_etag_is_answered_with_not_modified() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+    let client = reqwest::Client::new();
+    let post = |etag: &'static str| {
+        client
+            .post(format!("{proxy}/"))
+            .header(header::IF_NONE_MATCH, etag)
+            .json(&json!({ "breed": "revalidate" }))
+            .send()
+    };
+
+    // 클라이언트의 If-None-Match는 업스트림으로 가지 않으므로 스텁은 본문을 보내고,
+    // 프록시가 그 ETag와 비교해서 304로 답한다.
+    let res = post("\"v1\"").await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(res.headers()[header::ETAG], "\"v1\"");
+    assert!(res.bytes().await.unwrap().is_empty());
+
+    let res = post("\"v0\"").await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body["message"].as_array().unwrap().len(), 1);
+    assert_eq!(stub.hits("revalidate"), 2);
+}
+
+#[tokio::test]
+async fn num_pics_is_cached_separately() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (_, one) = fetch(&proxy, json!({ "breed": "husky" })).await;
+    let (_, three) = fetch(&proxy, json!({ "breed": "husky", "num_pics": 3 })).await;
+
+    assert_eq!(one["message"].as_array().unwrap().len(), 1);
+    assert_eq!(three["message"].as_array().unwrap().len(), 3);
+    assert_eq!(stub.hits("husky"), 2);
+}
+
+#[tokio::test]
+async fn not_found_is_not_cached_by_default() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    for _ in 0..2 {
+        let (status, body) = fetch(&proxy, json!({ "breed": "unknown" })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["status"], "error");
+    }
+
+    assert_eq!(stub.hits("unknown"), 2);
+    assert!(cache_entries(&proxy).await.is_empty());
+}
+
+#[tokio::test]
+async fn not_found_is_cached_with_negative_ttl() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(UpstreamConfig {
+        negative_ttl: Some(Duration::from_secs(5)),
+        ..upstream_config(base_url)
+    })
+    .await;
+
+    for _ in 0..2 {
+        let (status, _) = fetch(&proxy, json!({ "breed": "unknown" })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    assert_eq!(stub.hits("unknown"), 1);
+    let entries = cache_entries(&proxy).await;
+    assert_eq!(entries[0]["status"], 404);
+    assert!(entries[0]["expires_in_secs"].as_u64().unwrap() <= 5);
+}
+
+#[tokio::test]
+async fn no_store_response_is_not_cached() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    fetch(&proxy, json!({ "breed": "private" })).await;
+    fetch(&proxy, json!({ "breed": "private" })).await;
+
+    assert_eq!(stub.hits("private"), 2);
+}
+
+#[tokio::test]
+async fn no_cache_response_is_revalidated_before_reuse() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (_, first) = fetch(&proxy, json!({ "breed": "revalidate" })).await;
+    let (status, second) = fetch(&proxy, json!({ "breed": "revalidate" })).await;
+
+    // 매번 업스트림에 확인하지만 304를 받으면 저장한 본문을 그대로 쓴다.
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second, first);
+    assert_eq!(stub.hits("revalidate"), 2);
+}
+
+#[tokio::test]
+async fn no_cache_entry_is_not_served_while_circuit_is_open() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy_with(
+        UpstreamConfig {
+            max_retries: 0,
+            ..upstream_config(base_url)
+        },
+        BreakerConfig {
+            failure_threshold: 1,
+            ..breaker_config()
+        },
+    )
+    .await;
+
+    fetch(&proxy, json!({ "breed": "revalidate" })).await;
+    stub.set_down(true);
+    fetch(&proxy, json!({ "breed": "revalidate" })).await;
+
+    let (status, _) = fetch(&proxy, json!({ "breed": "revalidate" })).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn unavailable_upstream_is_retried() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (status, _) = fetch(&proxy, json!({ "breed": "flaky" })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stub.hits("flaky"), 2);
+}
+
+#[tokio::test]
+async fn persistent_failure_is_returned_and_not_cached() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{proxy}/"))
+        .json(&json!({ "breed": "down" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    // 첫 요청과 재시도 2번
+    assert_eq!(stub.hits("down"), 3);
+    assert!(cache_entries(&proxy).await.is_empty());
+}
+
+#[tokio::test]
+async fn unreachable_upstream_is_bad_gateway() {
+    // 주소만 얻고 바로 닫아서 아무도 듣지 않는 포트를 만든다.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let proxy = spawn_proxy(upstream_config(format!("http://{addr}"))).await;
+
+    let (status, body) = fetch(&proxy, json!({ "breed": "hound" })).await;
+
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(body.as_str().unwrap().contains("connect"));
+}
+
+#[tokio::test]
+async fn slow_upstream_is_gateway_timeout() {
+    let (_, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let (status, _) = fetch(&proxy, json!({ "breed": "slow" })).await;
+
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn concurrent_misses_share_one_upstream_request() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy(upstream_config(base_url)).await;
+
+    let requests = (0..5).map(|_| {
+        let proxy = proxy.clone();
+        async move { fetch(&proxy, json!({ "breed": "sleepy" })).await }
+    });
+    let responses = join_all(requests).await;
+
+    for (status, body) in &responses {
+        assert_eq!(*status, StatusCode::OK);
+        assert_eq!(body, &responses[0].1);
+    }
+    assert_eq!(stub.hits("sleepy"), 1);
+}
+
+#[tokio::test]
+async fn circuit_opens_after_consecutive_failures() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy_with(
+        UpstreamConfig {
+            max_retries: 0,
+            ..upstream_config(base_url)
+        },
+        BreakerConfig {
+            failure_threshold: 2,
+            ..breaker_config()
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+    let request = || {
+        client
+            .post(format!("{proxy}/"))
+            .json(&json!({ "breed": "down" }))
+            .send()
+    };
+
+    for _ in 0..2 {
+        let res = request().await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(res.headers().get(header::RETRY_AFTER).is_none());
+    }
+    assert_eq!(breaker_status(&proxy).await["state"], "open");
+
+    // 서킷이 열리면 업스트림에 보내지 않고 바로 실패한다.
+    let res = request().await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let retry_after = res.headers()[header::RETRY_AFTER].to_str().unwrap();
+    assert!((1..=30).contains(&retry_after.parse::<u64>().unwrap()));
+    assert_eq!(stub.hits("down"), 2);
+
+    let status = breaker_status(&proxy).await;
+    assert_eq!(status["trips"], 1);
+    assert_eq!(status["rejected"], 1);
+
+    let metrics = reqwest::get(format!("{proxy}/metrics"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("\nproxy_circuit_state 2\n"));
+    assert!(metrics.contains("\nproxy_circuit_trips_total 1\n"));
+}
+
+#[tokio::test]
+async fn stale_entry_is_served_while_circuit_is_open() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy_with(
+        UpstreamConfig {
+            max_retries: 0,
+            ..upstream_config(base_url)
+        },
+        BreakerConfig {
+            failure_threshold: 1,
+            ..breaker_config()
+        },
+    )
+    .await;
+
+    let (status, fresh) = fetch(&proxy, json!({ "breed": "brief" })).await;
+    assert_eq!(status, StatusCode::OK);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    stub.set_down(true);
+
+    // 이 실패로 서킷이 열린다.
+    let (status, _) = fetch(&proxy, json!({ "breed": "brief" })).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let res = reqwest::Client::new()
+        .post(format!("{proxy}/"))
+        .json(&json!({ "breed": "brief" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().contains_key(header::WARNING));
+    assert_eq!(res.json::<Value>().await.unwrap(), fresh);
+    assert_eq!(stub.hits("brief"), 2);
+}
+
+#[tokio::test]
+async fn successful_probe_closes_circuit() {
+    let (stub, base_url) = spawn_stub().await;
+    let proxy = spawn_proxy_with(
+        UpstreamConfig {
+            max_retries: 0,
+            ..upstream_config(base_url)
+        },
+        BreakerConfig {
+            failure_threshold: 1,
+            cool_down: Duration::from_millis(200),
+        },
+    )
+    .await;
+
+    stub.set_down(true);
+    fetch(&proxy, json!({ "breed": "hound" })).await;
+    assert_eq!(breaker_status(&proxy).await["state"], "open");
+
+    stub.set_down(false);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let (status, _) = fetch(&proxy, json!({ "breed": "hound" })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let status = breaker_status(&proxy).await;
+    assert_eq!(status["state"], "closed");
+    assert_eq!(status["consecutive_failures"], 0);
+}
+
+#[tokio::test]
+async fn disk_cache_survives_restart() {
+    let (stub, base_url) = spawn_stub().await;
+    let dir = tempfile::tempdir().unwrap();
+    let disk_config = DiskCacheConfig {
+        dir: dir.path().to_path_buf(),
+        max_bytes: 1024 * 1024,
+        max_stale: Duration::from_secs(60),
+    };
+
+    let disk = DiskCache::open(disk_config.clone()).await.unwrap();
+    let state = AppState::new(
+        memory_cache(),
+        upstream_config(base_url.clone()),
+        breaker_config(),
+    );
+    let proxy = serve_proxy(state.with_disk_cache(disk)).await;
+    let (_, before) = fetch(&proxy, json!({ "breed": "hound" })).await;
+
+    // 같은 디렉터리로 새 프록시를 띄우면 메모리 캐시는 비어 있어도 디스크에서 찾는다.
+    let disk = DiskCache::open(disk_config).await.unwrap();
+    let state = AppState::new(memory_cache(), upstream_config(base_url), breaker_config());
+    let proxy = serve_proxy(state.with_disk_cache(disk)).await;
+    assert!(cache_entries(&proxy).await.is_empty());
+    let (status, after) = fetch(&proxy, json!({ "breed": "hound" })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(before, after);
+    assert_eq!(stub.hits("hound"), 1);
+    // 디스크에서 찾은 항목은 메모리 캐시로 올라간다.
+    assert_eq!(cache_entries(&proxy).await.len(), 1);
+}
+
+/// 여러 요청을 동시에 보내고 모두 끝날 때까지 기다린다.
+async fn join_all<F>(futures: impl Iterator<Item = F>) -> Vec<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let handles = futures.map(tokio::spawn).collect::<Vec<_>>();
+    let mut outputs = Vec::new();
+    for handle in handles {
+        outputs.push(handle.await.unwrap());
+    }
+    outputs
+}
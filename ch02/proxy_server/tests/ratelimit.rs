@@ -0,0 +1,264 @@
+//! 요청 수 제한 통합 테스트
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    Router,
+    extract::Request,
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::Response,
+};
+use proxy_server::{
+    breaker::BreakerConfig,
+    cache::{Cache, CacheConfig},
+    ratelimit::{
+        AuthenticatedUser, KeyBy, MemoryStore, Quota, RateLimitStore, RateLimiter, RateLimits,
+    },
+    reverse::{Route, Routes},
+    router,
+    state::AppState,
+    upstream::UpstreamConfig,
+};
+
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}")
+}
+
+/// 모든 요청에 200으로 답하는 스텁 업스트림
+async fn spawn_upstream() -> String {
+    serve(Router::new().fallback(|| async { "ok" })).await
+}
+
+fn proxy_app(upstream: &str, rate_limits: RateLimits) -> Router {
+    let cache = Cache::new(CacheConfig {
+        default_ttl: Duration::from_secs(60),
+        max_bytes: 1024 * 1024,
+        max_stale: Duration::ZERO,
+        vary_headers: Vec::new(),
+    });
+    let config = UpstreamConfig {
+        base_url: upstream.to_string(),
+        timeout: Duration::from_secs(2),
+        connect_timeout: Duration::from_millis(200),
+        read_timeout: Duration::from_secs(1),
+        max_retries: 0,
+        retry_base_delay: Duration::from_millis(10),
+        negative_ttl: None,
+    };
+    let breaker = BreakerConfig {
+        failure_threshold: 5,
+        cool_down: Duration::from_secs(30),
+    };
+    let routes = Routes::new(vec![Route {
+        prefix: "/api".to_string(),
+        upstream: upstream.to_string(),
+    }]);
+    let state = AppState::new(cache, config, breaker)
+        .with_routes(routes)
+        .with_rate_limits(rate_limits);
+    router(state)
+}
+
+async fn spawn_proxy(upstream: &str, rate_limits: RateLimits) -> String {
+    serve(proxy_app(upstream, rate_limits)).await
+}
+
+/// 테스트용 인증 미들웨어. 알고 있는 토큰만 사용자로 인정한다.
+async fn fake_auth(mut request: Request, next: Next) -> Response {
+    let user = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| match value {
+            "Bearer alice-token" => Some("alice"),
+            "Bearer bob-token" => Some("bob"),
+            _ => None,
+        });
+    if let Some(user) = user {
+        request
+            .extensions_mut()
+            .insert(AuthenticatedUser(user.to_string()));
+    }
+    next.run(request).await
+}
+
+async fn spawn_proxy_with_auth(upstream: &str, rate_limits: RateLimits) -> String {
+    serve(proxy_app(upstream, rate_limits).layer(middleware::from_fn(fake_auth))).await
+}
+
+fn limiter(name: &'static str, quota: Quota, key_by: KeyBy) -> RateLimiter {
+    let store: Arc<dyn RateLimitStore> = Arc::new(MemoryStore::default());
+    RateLimiter::new(name, quota, key_by, store)
+}
+
+#[tokio::test]
+async fn rejects_requests_over_the_burst() {
+    let upstream = spawn_upstream().await;
+    let proxy = spawn_proxy(
+        &upstream,
+        RateLimits {
+            proxy: Some(limiter("proxy", Quota::per_minute(3), KeyBy::Ip)),
+            admin: None,
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    for remaining in ["2", "1", "0"] {
+        let res = client
+            .post(format!("{proxy}/api/items"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["ratelimit-limit"], "3");
+        assert_eq!(res.headers()["ratelimit-remaining"], remaining);
+        assert_eq!(res.headers()["ratelimit-policy"], "3;w=60");
+    }
+
+    let res = client
+        .post(format!("{proxy}/api/items"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(res.headers()["ratelimit-remaining"], "0");
+    // 토큰 하나는 20초마다 찬다.
+    assert_eq!(res.headers()[header::RETRY_AFTER], "20");
+    assert_eq!(res.headers()["ratelimit-reset"], "60");
+}
+
+#[tokio::test]
+async fn tokens_refill_over_time() {
+    let upstream = spawn_upstream().await;
+    let quota = Quota {
+        burst: 1,
+        period: Duration::from_millis(200),
+    };
+    let proxy = spawn_proxy(
+        &upstream,
+        RateLimits {
+            proxy: Some(limiter("proxy", quota, KeyBy::Ip)),
+            admin: None,
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+    let get = || client.get(format!("{proxy}/api/items")).send();
+
+    assert_eq!(get().await.unwrap().status(), StatusCode::OK);
+    let res = get().await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    // 1초보다 짧게 남았어도 0초라고 알려주지 않는다.
+    assert_eq!(res.headers()[header::RETRY_AFTER], "1");
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(get().await.unwrap().status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn users_have_separate_buckets() {
+    let upstream = spawn_upstream().await;
+    let proxy = spawn_proxy_with_auth(
+        &upstream,
+        RateLimits {
+            proxy: Some(limiter("proxy", Quota::per_minute(1), KeyBy::User)),
+            admin: None,
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+    let post = |token: &str| {
+        client
+            .post(format!("{proxy}/api/items"))
+            .bearer_auth(token)
+            .send()
+    };
+
+    assert_eq!(post("alice-token").await.unwrap().status(), StatusCode::OK);
+    assert_eq!(
+        post("alice-token").await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(post("bob-token").await.unwrap().status(), StatusCode::OK);
+
+    // 인증하지 않은 요청은 IP 주소로 구분한다.
+    let anonymous = || client.post(format!("{proxy}/api/items")).send();
+    assert_eq!(anonymous().await.unwrap().status(), StatusCode::OK);
+    assert_eq!(
+        anonymous().await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn unverified_authorization_does_not_bypass_the_limit() {
+    let upstream = spawn_upstream().await;
+    let proxy = spawn_proxy_with_auth(
+        &upstream,
+        RateLimits {
+            proxy: Some(limiter("proxy", Quota::per_minute(2), KeyBy::User)),
+            admin: None,
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    // 인증 미들웨어가 모르는 토큰은 요청마다 바꿔도 같은 IP 버킷을 쓴다.
+    let statuses = futures::future::join_all((0..3).map(|i| {
+        client
+            .post(format!("{proxy}/api/items"))
+            .bearer_auth(format!("made-up-{i}"))
+            .send()
+    }))
+    .await
+    .into_iter()
+    .map(|res| res.unwrap().status())
+    .collect::<Vec<_>>();
+    assert_eq!(
+        statuses
+            .iter()
+            .filter(|&&status| status == StatusCode::TOO_MANY_REQUESTS)
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn routes_are_limited_separately() {
+    let upstream = spawn_upstream().await;
+    let proxy = spawn_proxy(
+        &upstream,
+        RateLimits {
+            proxy: None,
+            admin: Some(limiter("admin", Quota::per_minute(1), KeyBy::Ip)),
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    let res = client
+        .get(format!("{proxy}/admin/cache"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client.get(format!("{proxy}/metrics")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // 제한을 설정하지 않은 경로는 헤더도 붙이지 않는다.
+    for _ in 0..3 {
+        let res = client
+            .post(format!("{proxy}/api/items"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key("ratelimit-limit"));
+    }
+}
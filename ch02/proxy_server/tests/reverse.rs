@@ -0,0 +1,471 @@
+//! 로컬 스텁 업스트림 앞에서 리버스 프록시 모드를 실행하는 통합 테스트
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use proxy_server::{
+    breaker::BreakerConfig,
+    cache::{Cache, CacheConfig},
+    reverse::{Route, Routes},
+    router,
+    state::AppState,
+    upstream::UpstreamConfig,
+};
+use serde_json::{Value, json};
+
+/// 받은 요청을 JSON으로 돌려주는 스텁 업스트림
+async fn echo(State(hits): State<Arc<AtomicUsize>>, request: Request) -> Response {
+    hits.fetch_add(1, Ordering::SeqCst);
+
+    let (parts, body) = request.into_parts();
+    if parts.uri.path() == "/stream" {
+        // 조각을 나눠서 보내는 응답
+        let chunks = futures::stream::iter(["one,", "two,", "three"]).then(|chunk| async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok::<_, std::io::Error>(Bytes::from(chunk))
+        });
+        return Body::from_stream(chunks).into_response();
+    }
+
+    if parts.uri.path() == "/session" {
+        // 요청한 클라이언트에게만 보내야 하는 쿠키를 담은 응답
+        return (
+            [
+                (header::CACHE_CONTROL, "max-age=60"),
+                (header::SET_COOKIE, "session=secret"),
+            ],
+            "session",
+        )
+            .into_response();
+    }
+
+    let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    let headers = parts
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), json!(value.to_str().unwrap())))
+        .collect::<serde_json::Map<_, _>>();
+
+    (
+        [
+            (header::CACHE_CONTROL, "max-age=60"),
+            (header::HeaderName::from_static("x-upstream"), "echo"),
+        ],
+        Json(json!({
+            "method": parts.method.as_str(),
+            "path": parts.uri.path(),
+            "query": parts.uri.query(),
+            "headers": headers,
+            "body": String::from_utf8_lossy(&body),
+        })),
+    )
+        .into_response()
+}
+
+async fn spawn_echo() -> (Arc<AtomicUsize>, String) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let app = Router::new().fallback(echo).with_state(hits.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (hits, format!("http://{addr}"))
+}
+
+const ETAG: &str = "\"v1\"";
+const LAST_MODIFIED: &str = "Wed, 01 Jan 2025 00:00:00 GMT";
+
+/// 1초 동안 캐시할 수 있고 조건부 요청에 304로 답하는 스텁 업스트림.
+/// 전체 응답과 304 응답 수를 따로 센다.
+async fn spawn_tagged() -> (Arc<AtomicUsize>, Arc<AtomicUsize>, String) {
+    let full = Arc::new(AtomicUsize::new(0));
+    let not_modified = Arc::new(AtomicUsize::new(0));
+
+    let app = Router::new().fallback({
+        let full = full.clone();
+        let not_modified = not_modified.clone();
+        move |headers: HeaderMap| async move {
+            let headers_out = [
+                (header::CACHE_CONTROL, "max-age=1"),
+                (header::ETAG, ETAG),
+                (header::LAST_MODIFIED, LAST_MODIFIED),
+            ];
+            if headers
+                .get(header::IF_NONE_MATCH)
+                .is_some_and(|tag| tag == ETAG)
+            {
+                not_modified.fetch_add(1, Ordering::SeqCst);
+                return (StatusCode::NOT_MODIFIED, headers_out).into_response();
+            }
+            full.fetch_add(1, Ordering::SeqCst);
+            (headers_out, "tagged body").into_response()
+        }
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (full, not_modified, format!("http://{addr}"))
+}
+
+async fn spawn_proxy(routes: Vec<Route>) -> String {
+    spawn_proxy_with_vary(routes, Vec::new()).await
+}
+
+async fn spawn_proxy_with_vary(routes: Vec<Route>, vary_headers: Vec<HeaderName>) -> String {
+    let cache = Cache::new(CacheConfig {
+        default_ttl: Duration::from_secs(60),
+        max_bytes: 1024 * 1024,
+        max_stale: Duration::from_secs(60),
+        vary_headers,
+    });
+    let upstream = UpstreamConfig {
+        base_url: "http://127.0.0.1:9".to_string(),
+        timeout: Duration::from_secs(2),
+        connect_timeout: Duration::from_millis(200),
+        read_timeout: Duration::from_secs(1),
+        max_retries: 0,
+        retry_base_delay: Duration::from_millis(10),
+        negative_ttl: None,
+    };
+    let breaker = BreakerConfig {
+        failure_threshold: 5,
+        cool_down: Duration::from_secs(30),
+    };
+    let state = AppState::new(cache, upstream, breaker).with_routes(Routes::new(routes));
+    let app = router(state).into_make_service_with_connect_info::<SocketAddr>();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}")
+}
+
+fn route(prefix: &str, upstream: &str) -> Route {
+    Route {
+        prefix: prefix.to_string(),
+        upstream: upstream.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn forwards_method_path_query_and_body() {
+    let (_, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+
+    let res = reqwest::Client::new()
+        .put(format!("{proxy}/api/users/1?verbose=true"))
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-upstream"], "echo");
+
+    let echoed = res.json::<Value>().await.unwrap();
+    assert_eq!(echoed["method"], "PUT");
+    assert_eq!(echoed["path"], "/users/1");
+    assert_eq!(echoed["query"], "verbose=true");
+    assert_eq!(echoed["body"], "hello");
+    assert_eq!(echoed["headers"]["content-type"], "text/plain");
+}
+
+#[tokio::test]
+async fn longest_prefix_wins_on_segment_boundary() {
+    let (_, api) = spawn_echo().await;
+    let (_, v2) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![
+        route("/api", &api),
+        route("/api/v2/", &format!("{v2}/next")),
+    ])
+    .await;
+    let client = reqwest::Client::new();
+
+    let echoed = client
+        .delete(format!("{proxy}/api/v2/items"))
+        .send()
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(echoed["path"], "/next/items");
+
+    let echoed = client
+        .delete(format!("{proxy}/api"))
+        .send()
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(echoed["path"], "/");
+
+    let res = client.get(format!("{proxy}/apis")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn strips_hop_by_hop_and_adds_forwarded_headers() {
+    let (_, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+
+    let echoed = reqwest::Client::new()
+        .post(format!("{proxy}/api/echo"))
+        .header(header::CONNECTION, "x-secret")
+        .header("x-secret", "hidden")
+        .header(header::PROXY_AUTHORIZATION, "Basic abc")
+        .header("x-forwarded-for", "10.0.0.1")
+        .header("x-custom", "kept")
+        .send()
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    let headers = &echoed["headers"];
+
+    assert!(headers.get("x-secret").is_none());
+    assert!(headers.get("proxy-authorization").is_none());
+    assert_eq!(headers["x-custom"], "kept");
+    assert_eq!(headers["x-forwarded-for"], "10.0.0.1, 127.0.0.1");
+    assert_eq!(headers["x-forwarded-proto"], "http");
+    assert_eq!(
+        headers["x-forwarded-host"],
+        proxy.trim_start_matches("http://")
+    );
+}
+
+#[tokio::test]
+async fn get_responses_are_cached_with_headers() {
+    let (hits, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        let res = client
+            .get(format!("{proxy}/api/items?page=1"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["x-upstream"], "echo");
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            HeaderValue::from_static("application/json")
+        );
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // 쿼리가 다르면 따로 캐시한다.
+    client
+        .get(format!("{proxy}/api/items?page=2"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn cached_requests_forward_only_keyed_headers() {
+    let (hits, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy_with_vary(
+        vec![route("/api", &upstream)],
+        vec![header::ACCEPT_LANGUAGE],
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    let get = |language: &'static str| {
+        client
+            .get(format!("{proxy}/api/negotiated"))
+            .header(header::ACCEPT_LANGUAGE, language)
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .header(header::ACCEPT, "text/html")
+            .header("x-custom", "dropped")
+            .send()
+    };
+
+    let echoed = get("ko").await.unwrap().json::<Value>().await.unwrap();
+    let headers = echoed["headers"].as_object().unwrap();
+    assert_eq!(headers["accept-language"], "ko");
+    for name in ["accept-encoding", "x-custom", "x-forwarded-for"] {
+        assert!(!headers.contains_key(name), "{name}");
+    }
+    // reqwest가 붙이는 기본값만 간다.
+    assert_eq!(headers["accept"], "*/*");
+
+    // 키에 들어간 헤더가 다르면 따로 캐시하고, 같으면 캐시한 응답을 쓴다.
+    let echoed = get("en").await.unwrap().json::<Value>().await.unwrap();
+    assert_eq!(echoed["headers"]["accept-language"], "en");
+    get("ko").await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn personal_and_unsafe_requests_are_not_cached() {
+    let (hits, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        client
+            .get(format!("{proxy}/api/me"))
+            .header(header::AUTHORIZATION, "Bearer token")
+            .send()
+            .await
+            .unwrap();
+        client.post(format!("{proxy}/api/me")).send().await.unwrap();
+    }
+
+    assert_eq!(hits.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn set_cookie_is_not_shared_through_the_cache() {
+    let (hits, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        let res = client
+            .get(format!("{proxy}/api/session"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(header::SET_COOKIE));
+        assert_eq!(res.text().await.unwrap(), "session");
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // 캐시를 거치지 않는 요청에는 그대로 전달한다.
+    let res = client
+        .post(format!("{proxy}/api/session"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()[header::SET_COOKIE], "session=secret");
+}
+
+#[tokio::test]
+async fn streams_request_and_response_bodies() {
+    let (_, upstream) = spawn_echo().await;
+    let proxy = spawn_proxy(vec![route("/api", &upstream)]).await;
+
+    // 요청 본문도 길이를 모르는 스트림으로 보낸다.
+    let chunks = futures::stream::iter(["a", "b", "c"])
+        .map(|chunk| Ok::<_, std::io::Error>(Bytes::from(chunk)));
+    let echoed = reqwest::Client::new()
+        .post(format!("{proxy}/api/upload"))
+        .body(reqwest::Body::wrap_stream(chunks))
+        .send()
+        .await
+        .unwrap()
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(echoed["body"], "abc");
+    assert_eq!(echoed["headers"]["transfer-encoding"], "chunked");
+
+    let streamed = reqwest::Client::new()
+        .post(format!("{proxy}/api/stream"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(streamed, "one,two,three");
+}
+
+#[tokio::test]
+async fn unknown_prefix_is_not_found() {
+    let proxy = spawn_proxy(Vec::new()).await;
+
+    let res = reqwest::get(format!("{proxy}/anything")).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn expired_entry_is_revalidated() {
+    let (full, not_modified, upstream) = spawn_tagged().await;
+    let proxy = spawn_proxy(vec![route("/tagged", &upstream)]).await;
+
+    let first = reqwest::get(format!("{proxy}/tagged/item")).await.unwrap();
+    assert_eq!(first.text().await.unwrap(), "tagged body");
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    let second = reqwest::get(format!("{proxy}/tagged/item")).await.unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(second.headers()[header::ETAG], ETAG);
+    assert_eq!(second.text().await.unwrap(), "tagged body");
+
+    assert_eq!(full.load(Ordering::SeqCst), 1);
+    assert_eq!(not_modified.load(Ordering::SeqCst), 1);
+
+    // 다시 확인한 항목은 새 캐시 기간 동안 업스트림에 묻지 않는다.
+    reqwest::get(format!("{proxy}/tagged/item")).await.unwrap();
+    assert_eq!(not_modified.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn client_conditional_requests_are_answered_by_proxy() {
+    let (full, not_modified, upstream) = spawn_tagged().await;
+    let proxy = spawn_proxy(vec![route("/tagged", &upstream)]).await;
+    let client = reqwest::Client::new();
+    let get = |name: header::HeaderName, value: &'static str| {
+        client
+            .get(format!("{proxy}/tagged/item"))
+            .header(name, value)
+            .send()
+    };
+
+    // 캐시가 비어 있어도 업스트림에는 조건 없이 요청하고, 받은 응답으로 확인한다.
+    let res = get(header::IF_NONE_MATCH, ETAG).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(res.headers()[header::ETAG], ETAG);
+    assert_eq!(full.load(Ordering::SeqCst), 1);
+    assert_eq!(not_modified.load(Ordering::SeqCst), 0);
+
+    let res = get(header::IF_NONE_MATCH, "\"v0\", W/\"v1\"")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+    let res = get(header::IF_NONE_MATCH, "\"v0\"").await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "tagged body");
+
+    let res = get(header::IF_MODIFIED_SINCE, LAST_MODIFIED).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+    let res = get(header::IF_MODIFIED_SINCE, "Tue, 31 Dec 2024 00:00:00 GMT")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    assert_eq!(full.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn parses_route_spec() {
+    assert!(Routes::parse("/api=http://a, /img=http://b").is_ok());
+    assert!(Routes::parse("").unwrap().is_empty());
+    assert!(Routes::parse("api=http://a").is_err());
+    assert!(Routes::parse("/api").is_err());
+}
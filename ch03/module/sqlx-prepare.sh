@@ -0,0 +1,43 @@
+#!/bin/sh
+# sqlx 쿼리 매크로의 메타데이터(.sqlx)를 지금 데이터베이스 스키마로 다시 만든다.
+#
+#   ./sqlx-prepare.sh          .sqlx를 갱신한다. 쿼리나 마이그레이션을 바꾼 뒤에 실행하고 함께 커밋한다.
+#   ./sqlx-prepare.sh --check  .sqlx가 스키마, 쿼리와 다르면 실패한다. CI에서 실행한다.
+#
+# DATABASE_URL(.env)의 데이터베이스에 마이그레이션이 모두 적용되어 있어야 한다.
+# sqlx-cli가 설치되어 있으면 `cargo sqlx prepare`를 그대로 쓴다.
+set -eu
+cd "$(dirname "$0")"
+
+if [ "${1:-}" != "" ] && [ "$1" != "--check" ]; then
+    echo "usage: $0 [--check]" >&2
+    exit 2
+fi
+
+if cargo sqlx --version >/dev/null 2>&1; then
+    exec env SQLX_OFFLINE=false cargo sqlx prepare ${1:+"$1"} -- --all-targets
+fi
+
+# sqlx-cli가 없으면 매크로가 SQLX_OFFLINE_DIR에 메타데이터를 쓰게 해서 직접 만든다.
+out=$(mktemp -d)
+trap 'rm -rf "$out"' EXIT
+
+# 매크로를 다시 실행하도록 따로 둔 빌드 디렉터리에서 이 크레이트만 지운다.
+target=target/sqlx-prepare
+cargo clean --offline -q -p module --target-dir "$target"
+SQLX_OFFLINE=false SQLX_OFFLINE_DIR="$out" \
+    cargo check --offline -q --all-targets --target-dir "$target"
+
+if [ "${1:-}" = "--check" ]; then
+    if ! diff -r .sqlx "$out" >/dev/null 2>&1; then
+        echo ".sqlx is out of date. Run ./sqlx-prepare.sh and commit the changes." >&2
+        diff -r .sqlx "$out" >&2 || true
+        exit 1
+    fi
+    echo ".sqlx is up to date"
+else
+    rm -rf .sqlx
+    mkdir .sqlx
+    cp "$out"/*.json .sqlx/
+    echo "query data written to .sqlx"
+fi
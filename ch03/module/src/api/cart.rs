@@ -0,0 +1,108 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api::users::AppError,
+    db::{
+        CartModel, add_cart_line_to_database, delete_cart_line_from_database,
+        get_cart_from_database,
+    },
+};
+
+#[derive(Serialize)]
+pub struct CartLine {
+    user_id: i32,
+    product_id: i32,
+    quantity: i32,
+}
+
+impl From<CartModel> for CartLine {
+    fn from(value: CartModel) -> Self {
+        CartLine {
+            user_id: value.user_id,
+            product_id: value.product_id,
+            quantity: value.quantity,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CartQuery {
+    user_id: i32,
+    product_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct AddCartLine {
+    user_id: i32,
+    product_id: i32,
+    quantity: Option<i32>,
+}
+
+/// "GET /cart" 핸들러
+/// 유저의 장바구니를 반환한다.
+pub async fn get_cart(
+    State(conn): State<Pool<Postgres>>,
+    Query(params): Query<CartQuery>,
+) -> Result<Json<Vec<CartLine>>, AppError> {
+    get_cart_from_database(&conn, params.user_id)
+        .await
+        .map(|lines| Json(lines.into_iter().map(CartLine::from).collect()))
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
+}
+
+/// "POST /cart" 핸들러
+/// 장바구니에 상품을 담는다. 수량을 생략하면 1개를 담는다.
+pub async fn post_cart(
+    State(conn): State<Pool<Postgres>>,
+    Json(line): Json<AddCartLine>,
+) -> Result<Json<CartLine>, AppError> {
+    let quantity = line.quantity.unwrap_or(1);
+    if quantity <= 0 {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "Quantity must be positive",
+        ));
+    }
+
+    add_cart_line_to_database(&conn, line.user_id, line.product_id, quantity)
+        .await
+        .map(|line| Json(line.into()))
+        .map_err(|err| match err {
+            sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                AppError::new(StatusCode::NOT_FOUND, "User or product not found")
+            }
+            _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        })
+}
+
+/// "DELETE /cart" 핸들러
+/// 장바구니에서 상품을 뺀다.
+pub async fn delete_cart(
+    State(conn): State<Pool<Postgres>>,
+    Query(params): Query<CartQuery>,
+) -> Result<Json<&'static str>, AppError> {
+    let Some(product_id) = params.product_id else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "Product id not provided",
+        ));
+    };
+
+    match delete_cart_line_from_database(&conn, params.user_id, product_id).await {
+        Ok(_) => Ok(Json("Deleted")),
+        Err(sqlx::Error::RowNotFound) => Err(AppError::new(
+            StatusCode::NOT_FOUND,
+            "Product not found in cart",
+        )),
+        Err(_) => Err(AppError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+        )),
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use serde::{Deserialize, Serialize};
@@ -11,14 +11,46 @@ use sqlx::{Pool, Postgres};
 use crate::{
     api::users::AppError,
     db::{
-        delete_category_from_database, get_all_categories_from_database,
-        get_categories_by_name_from_database, insert_category_to_database,
+        CategoryModel, delete_category_from_database, get_all_categories_from_database,
+        get_categories_by_name_from_database, get_category_from_database,
+        get_category_subtree_from_database, insert_category_to_database, move_category_in_database,
     },
 };
 
 #[derive(Serialize, Deserialize)]
 pub struct Category {
     name: String,
+    parent: Option<String>,
+}
+
+impl From<CategoryModel> for Category {
+    fn from(value: CategoryModel) -> Self {
+        Category {
+            name: value.name,
+            parent: value.parent,
+        }
+    }
+}
+
+/// 하위 카테고리를 포함한 카테고리 트리
+#[derive(Serialize)]
+pub struct CategoryTree {
+    name: String,
+    children: Vec<CategoryTree>,
+}
+
+impl CategoryTree {
+    /// `name`을 루트로 하는 트리를 만든다.
+    /// `children`은 부모 이름을 키로 하는 하위 카테고리 이름 목록이다.
+    fn build(name: String, children: &mut HashMap<String, Vec<String>>) -> Self {
+        let child_names = children.remove(&name).unwrap_or_default();
+        let children = child_names
+            .into_iter()
+            .map(|child| CategoryTree::build(child, children))
+            .collect();
+
+        CategoryTree { name, children }
+    }
 }
 
 /// GET category 핸들러
@@ -34,29 +66,93 @@ pub async fn get_category(
 
     result
         .map(|categories| {
-            let categories = categories
-                .into_iter()
-                .map(|cat| Category { name: cat.name })
-                .collect();
+            let categories = categories.into_iter().map(Category::from).collect();
             Json(categories)
         })
         .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
 }
 
+/// GET category/{name} 핸들러
+/// 카테고리 하나를 반환한다.
+pub async fn get_category_by_name(
+    State(conn): State<Pool<Postgres>>,
+    Path(name): Path<String>,
+) -> Result<Json<Category>, AppError> {
+    get_category_from_database(&conn, &name)
+        .await
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .map(|category| Json(category.into()))
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Category not found"))
+}
+
 /// POST category 핸들러
 /// 카테고리를 생성한다.
 pub async fn post_category(
     State(conn): State<Pool<Postgres>>,
     Json(category): Json<Category>,
 ) -> Result<Json<Category>, AppError> {
-    insert_category_to_database(&conn, &category.name)
+    insert_category_to_database(&conn, &category.name, category.parent.as_deref())
         .await
-        .map(|category| {
-            Json(Category {
-                name: category.name,
-            })
+        .map(|category| Json(category.into()))
+        .map_err(|err| match err {
+            sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                AppError::new(StatusCode::NOT_FOUND, "Parent category not found")
+            }
+            _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
         })
-        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
+}
+
+/// PUT category 핸들러
+/// 카테고리를 `parent` 아래로 옮긴다. 하위 카테고리도 함께 옮겨진다.
+pub async fn put_category(
+    State(conn): State<Pool<Postgres>>,
+    Json(category): Json<Category>,
+) -> Result<Json<Category>, AppError> {
+    match move_category_in_database(&conn, &category.name, category.parent.as_deref()).await {
+        Ok(Some(category)) => Ok(Json(category.into())),
+        Ok(None) => Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Category cannot be moved under itself or its subcategory",
+        )),
+        Err(sqlx::Error::RowNotFound) => {
+            Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"))
+        }
+        Err(sqlx::Error::Database(err)) if err.is_foreign_key_violation() => Err(AppError::new(
+            StatusCode::NOT_FOUND,
+            "Parent category not found",
+        )),
+        Err(_) => Err(AppError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+        )),
+    }
+}
+
+/// GET category tree 핸들러
+/// 카테고리와 모든 하위 카테고리를 트리 형태로 반환한다.
+pub async fn get_category_tree(
+    State(conn): State<Pool<Postgres>>,
+    Path(name): Path<String>,
+) -> Result<Json<CategoryTree>, AppError> {
+    let subtree = get_category_subtree_from_database(&conn, &name)
+        .await
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    if subtree.is_empty() {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"));
+    }
+
+    let mut children = HashMap::<String, Vec<String>>::new();
+    for category in subtree {
+        if let Some(parent) = category.parent
+            && category.name != name
+        {
+            children.entry(parent).or_default().push(category.name);
+        }
+    }
+    children.values_mut().for_each(|names| names.sort());
+
+    Ok(Json(CategoryTree::build(name, &mut children)))
 }
 
 /// DELETE category 핸들러
@@ -77,6 +173,10 @@ pub async fn delete_category(
         Err(sqlx::Error::RowNotFound) => {
             Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"))
         }
+        Err(sqlx::Error::Database(err)) if err.is_foreign_key_violation() => Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Category still has products or subcategories",
+        )),
         Err(_) => Err(AppError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
             "Database error",
@@ -0,0 +1,281 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{
+        Multipart, Path, State,
+        multipart::{Field, MultipartError},
+    },
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use tokio::{fs::File, io::AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::{
+    api::users::AppError,
+    db::{
+        ProductImageModel, get_product_image_from_database, get_product_images_from_database,
+        insert_product_image_to_database, product_exists,
+    },
+    storage::{ImageStorage, SNIFF_LEN, sniff_image_type},
+};
+
+/// 이미지는 새로 올릴 때마다 id가 바뀌므로 오래 캐시해도 된다.
+const IMAGE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[derive(Serialize)]
+pub struct ProductImage {
+    id: i32,
+    product_id: i32,
+    content_type: String,
+    size: i64,
+    url: String,
+}
+
+impl From<ProductImageModel> for ProductImage {
+    fn from(value: ProductImageModel) -> Self {
+        ProductImage {
+            url: format!("/product/{}/images/{}", value.product_id, value.id),
+            id: value.id,
+            product_id: value.product_id,
+            content_type: value.content_type,
+            size: value.size,
+        }
+    }
+}
+
+fn multipart_error(err: MultipartError) -> AppError {
+    AppError::new(err.status(), err.body_text())
+}
+
+/// 필드를 청크 단위로 파일에 쓴다.
+/// 전체를 메모리에 올리지 않고, 앞부분으로 이미지 형식을 확인한 뒤 크기 제한을 검사하면서 쓴다.
+/// 성공하면 (Content-Type, 크기)를 반환한다.
+async fn save_image_field(
+    field: &mut Field<'_>,
+    file: &mut File,
+    max_bytes: usize,
+) -> Result<(&'static str, usize), AppError> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    let mut content_type = None;
+    let mut size = 0;
+
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        size += chunk.len();
+        if size > max_bytes {
+            return Err(AppError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Image must be at most {max_bytes} bytes"),
+            ));
+        }
+
+        if content_type.is_none() {
+            let needed = SNIFF_LEN - head.len();
+            head.extend_from_slice(&chunk[..needed.min(chunk.len())]);
+            if head.len() == SNIFF_LEN {
+                content_type = Some(sniff_image_type(&head).ok_or_else(unsupported_image)?);
+            }
+        }
+
+        file.write_all(&chunk)
+            .await
+            .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Storage error"))?;
+    }
+
+    // 파일이 SNIFF_LEN보다 짧은 경우
+    let content_type = match content_type {
+        Some(content_type) => content_type,
+        None => sniff_image_type(&head).ok_or_else(unsupported_image)?,
+    };
+
+    file.flush()
+        .await
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Storage error"))?;
+
+    Ok((content_type, size))
+}
+
+fn unsupported_image() -> AppError {
+    AppError::new(
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "Only JPEG, PNG, GIF and WebP images are supported",
+    )
+}
+
+/// 디스크에 저장했지만 아직 기록하지 않은 이미지
+struct SavedImage {
+    file_name: String,
+    content_type: &'static str,
+    size: usize,
+}
+
+/// 파일 이름이 있는 필드를 모두 이미지 파일로 저장한다. 데이터베이스 연결은 쓰지 않는다.
+/// 파일을 만들면 바로 `file_names`에 넣어서 실패했을 때 호출한 쪽이 지울 수 있게 한다.
+async fn save_images(
+    storage: &ImageStorage,
+    multipart: &mut Multipart,
+    file_names: &mut Vec<String>,
+) -> Result<Vec<SavedImage>, AppError> {
+    let mut saved = Vec::new();
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.file_name().is_none() {
+            continue;
+        }
+
+        let file_name = Uuid::new_v4().to_string();
+        let mut file = File::create(storage.path(&file_name))
+            .await
+            .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Storage error"))?;
+        file_names.push(file_name.clone());
+
+        let (content_type, size) =
+            save_image_field(&mut field, &mut file, storage.max_image_bytes()).await?;
+        saved.push(SavedImage {
+            file_name,
+            content_type,
+            size,
+        });
+    }
+
+    if saved.is_empty() {
+        return Err(AppError::new(StatusCode::BAD_REQUEST, "No image provided"));
+    }
+
+    Ok(saved)
+}
+
+/// 저장한 이미지를 트랜잭션 하나로 모두 기록한다.
+async fn record_images(
+    conn: &Pool<Postgres>,
+    product_id: i32,
+    saved: &[SavedImage],
+) -> Result<Vec<ProductImage>, AppError> {
+    let database_error = |err| match err {
+        // 업로드하는 사이에 상품이 지워진 경우
+        sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+            AppError::new(StatusCode::NOT_FOUND, "Product not found")
+        }
+        _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    };
+
+    let mut tx = conn.begin().await.map_err(database_error)?;
+    let mut images = Vec::with_capacity(saved.len());
+    for image in saved {
+        let image = insert_product_image_to_database(
+            &mut tx,
+            product_id,
+            &image.file_name,
+            image.content_type,
+            image.size as i64,
+        )
+        .await
+        .map_err(database_error)?;
+        images.push(ProductImage::from(image));
+    }
+    tx.commit().await.map_err(database_error)?;
+
+    Ok(images)
+}
+
+/// "POST /product/{id}/images" 핸들러
+/// 파일 이름이 있는 필드를 모두 이미지로 저장한다.
+/// 느린 업로드가 연결을 오래 잡지 않도록 파일을 모두 받은 뒤에 짧은 트랜잭션 하나로 기록한다.
+/// 한 장이라도 실패하면 기록을 모두 되돌리고 이번 요청에서 쓴 파일을 모두 지운다.
+pub async fn post_product_images(
+    State(conn): State<Pool<Postgres>>,
+    State(storage): State<ImageStorage>,
+    Path(product_id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<ProductImage>>, AppError> {
+    // 없는 상품이면 본문을 받기 전에 거절한다.
+    match product_exists(&conn, product_id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(AppError::new(StatusCode::NOT_FOUND, "Product not found")),
+        Err(_) => {
+            return Err(AppError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            ));
+        }
+    }
+
+    let mut file_names = Vec::new();
+    let result = match save_images(&storage, &mut multipart, &mut file_names).await {
+        Ok(saved) => record_images(&conn, product_id, &saved).await,
+        Err(err) => Err(err),
+    };
+
+    if result.is_err() {
+        storage.remove_files(&file_names).await;
+    }
+
+    result.map(Json)
+}
+
+/// "GET /product/{id}/images" 핸들러
+/// 상품의 이미지 목록을 반환한다.
+pub async fn get_product_images(
+    State(conn): State<Pool<Postgres>>,
+    Path(product_id): Path<i32>,
+) -> Result<Json<Vec<ProductImage>>, AppError> {
+    get_product_images_from_database(&conn, product_id)
+        .await
+        .map(|images| Json(images.into_iter().map(ProductImage::from).collect()))
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))
+}
+
+/// "GET /product/{id}/images/{image_id}" 핸들러
+/// 저장된 이미지를 스트리밍으로 내려준다.
+pub async fn get_product_image(
+    State(conn): State<Pool<Postgres>>,
+    State(storage): State<ImageStorage>,
+    Path((product_id, image_id)): Path<(i32, i32)>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let image = match get_product_image_from_database(&conn, product_id, image_id).await {
+        Ok(image) => image,
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(AppError::new(StatusCode::NOT_FOUND, "Image not found"));
+        }
+        Err(_) => {
+            return Err(AppError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            ));
+        }
+    };
+
+    let etag = format!("\"{}\"", image.file_name);
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+    {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, IMAGE_CACHE_CONTROL.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    let file = File::open(storage.path(&image.file_name))
+        .await
+        .map_err(|_| AppError::new(StatusCode::NOT_FOUND, "Image file not found"))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, image.content_type),
+            (header::CONTENT_LENGTH, image.size.to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, IMAGE_CACHE_CONTROL.to_string()),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
@@ -1,3 +1,6 @@
+pub mod cart;
 pub mod category;
+pub mod image;
+pub mod order;
 pub mod product;
 pub mod users;
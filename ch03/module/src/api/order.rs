@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api::users::AppError,
+    db::{
+        OrderLineModel, OrderModel, OrderStatus, get_order_by_id_from_database,
+        get_order_lines_from_database, get_orders_from_database, place_order_to_database,
+        update_order_status_from_database,
+    },
+};
+
+#[derive(Serialize)]
+pub struct OrderLine {
+    product_id: i32,
+    title: String,
+    price: i32,
+    quantity: i32,
+}
+
+impl From<OrderLineModel> for OrderLine {
+    fn from(value: OrderLineModel) -> Self {
+        OrderLine {
+            product_id: value.product_id,
+            title: value.title,
+            price: value.price,
+            quantity: value.quantity,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Order {
+    id: i32,
+    user_id: i32,
+    status: String,
+    total: i64,
+    lines: Vec<OrderLine>,
+}
+
+impl Order {
+    fn new(order: OrderModel, lines: Vec<OrderLineModel>) -> Self {
+        let total = lines
+            .iter()
+            .map(|line| i64::from(line.price) * i64::from(line.quantity))
+            .sum();
+
+        Order {
+            id: order.id,
+            user_id: order.user_id,
+            status: order.status,
+            total,
+            lines: lines.into_iter().map(OrderLine::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OrderQuery {
+    user_id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct PlaceOrder {
+    user_id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateOrderStatus {
+    id: i32,
+    status: OrderStatus,
+}
+
+/// "GET /orders" 핸들러
+/// 유저의 주문 목록을 주문 항목과 함께 반환한다.
+pub async fn get_orders(
+    State(conn): State<Pool<Postgres>>,
+    Query(params): Query<OrderQuery>,
+) -> Result<Json<Vec<Order>>, AppError> {
+    let db_error = |_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+
+    let orders = get_orders_from_database(&conn, params.user_id)
+        .await
+        .map_err(db_error)?;
+    let order_ids = orders.iter().map(|order| order.id).collect::<Vec<_>>();
+    let mut lines = HashMap::<i32, Vec<OrderLineModel>>::new();
+    for line in get_order_lines_from_database(&conn, &order_ids)
+        .await
+        .map_err(db_error)?
+    {
+        lines.entry(line.order_id).or_default().push(line);
+    }
+
+    let orders = orders
+        .into_iter()
+        .map(|order| {
+            let order_lines = lines.remove(&order.id).unwrap_or_default();
+            Order::new(order, order_lines)
+        })
+        .collect();
+
+    Ok(Json(orders))
+}
+
+/// "POST /orders" 핸들러
+/// 장바구니에 담긴 상품으로 주문을 만든다. 없는 유저면 404, 장바구니가 비어 있으면 400을 반환한다.
+pub async fn post_order(
+    State(conn): State<Pool<Postgres>>,
+    Json(order): Json<PlaceOrder>,
+) -> Result<Json<Order>, AppError> {
+    match place_order_to_database(&conn, order.user_id).await {
+        Ok((order, lines)) => Ok(Json(Order::new(order, lines))),
+        Err(sqlx::Error::RowNotFound) => {
+            Err(AppError::new(StatusCode::BAD_REQUEST, "Cart is empty"))
+        }
+        // 주문을 먼저 만들므로 없는 유저는 외래 키 위반으로 드러난다.
+        Err(sqlx::Error::Database(err)) if err.is_foreign_key_violation() => {
+            Err(AppError::new(StatusCode::NOT_FOUND, "User not found"))
+        }
+        Err(_) => Err(AppError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+        )),
+    }
+}
+
+/// "PUT /orders" 핸들러
+/// 주문 상태를 바꾼다. 허용되지 않는 상태 변경은 409를 반환한다.
+pub async fn put_order(
+    State(conn): State<Pool<Postgres>>,
+    Json(update): Json<UpdateOrderStatus>,
+) -> Result<Json<Order>, AppError> {
+    let order = match get_order_by_id_from_database(&conn, update.id).await {
+        Ok(order) => order,
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(AppError::new(StatusCode::NOT_FOUND, "Order not found"));
+        }
+        Err(_) => {
+            return Err(AppError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            ));
+        }
+    };
+
+    let current = order
+        .status
+        .parse::<OrderStatus>()
+        .map_err(|err| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    if !current.can_transition_to(update.status) {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "Cannot change order status from {current} to {}",
+                update.status
+            ),
+        ));
+    }
+
+    let order = update_order_status_from_database(&conn, update.id, current, update.status)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::RowNotFound => AppError::new(
+                StatusCode::CONFLICT,
+                "Order status was changed concurrently",
+            ),
+            _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        })?;
+    let lines = get_order_lines_from_database(&conn, &[order.id])
+        .await
+        .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(Json(Order::new(order, lines)))
+}
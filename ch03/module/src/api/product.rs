@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::{
     Json,
     extract::{Query, State},
@@ -8,7 +10,8 @@ use sqlx::{Pool, Postgres};
 
 use crate::{
     api::users::AppError,
-    db::{ProductModel, insert_product, select_product, update_product},
+    db::{self, ProductModel, insert_product, select_product, update_product},
+    storage::ImageStorage,
 };
 
 #[derive(Deserialize)]
@@ -19,6 +22,16 @@ pub struct UpsertModel {
     category: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct ProductQuery {
+    id: Option<i32>,
+    title: Option<String>,
+    price: Option<i32>,
+    category: Option<String>,
+    /// true면 하위 카테고리에 속한 상품도 함께 가져온다.
+    include_subcategories: Option<bool>,
+}
+
 #[derive(Serialize)]
 pub struct Product {
     id: i32,
@@ -40,7 +53,7 @@ impl From<ProductModel> for Product {
 
 pub async fn get_product(
     State(conn): State<Pool<Postgres>>,
-    Query(params): Query<UpsertModel>,
+    Query(params): Query<ProductQuery>,
 ) -> Result<Json<Vec<Product>>, AppError> {
     let products = select_product(
         &conn,
@@ -48,6 +61,7 @@ pub async fn get_product(
         params.title,
         params.price,
         params.category,
+        params.include_subcategories.unwrap_or(false),
     )
     .await
     .map_err(|_| AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database Error"))?
@@ -112,3 +126,37 @@ pub async fn put_product(
         )),
     }
 }
+
+/// 상품을 지운다. 상품 이미지 파일도 함께 지운다.
+pub async fn delete_product(
+    State(executor): State<Pool<Postgres>>,
+    State(storage): State<ImageStorage>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<&'static str>, AppError> {
+    let Some(id) = params.get("id") else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "id field is not set",
+        ));
+    };
+    let id = id
+        .parse::<i32>()
+        .map_err(|_| AppError::new(StatusCode::BAD_REQUEST, "id must be an integer"))?;
+
+    let file_names = match db::delete_product(&executor, id).await {
+        Ok(file_names) => file_names,
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(AppError::new(StatusCode::NOT_FOUND, "Product not found"));
+        }
+        Err(_) => {
+            return Err(AppError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+            ));
+        }
+    };
+    // 기록을 지운 뒤에 파일을 지워서 남은 기록이 없는 파일을 가리키지 않게 한다.
+    storage.remove_files(&file_names).await;
+
+    Ok(Json("Product deleted"))
+}
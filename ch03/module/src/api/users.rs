@@ -9,6 +9,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::{Pool, Postgres};
+use username::UsernameError;
 
 use crate::db::{
     UserModel, delete_user_from_database, get_user_from_database, insert_user_to_database,
@@ -36,6 +37,24 @@ impl IntoResponse for AppError {
     }
 }
 
+/// 유저 이름 규칙에 맞지 않으면 400으로 바꾼다.
+impl From<UsernameError> for AppError {
+    fn from(err: UsernameError) -> Self {
+        AppError::new(StatusCode::BAD_REQUEST, err.to_string())
+    }
+}
+
+/// 유저 이름이 겹치면 409, 그 밖의 오류는 500으로 바꾼다.
+fn user_write_error(err: sqlx::Error) -> AppError {
+    match err {
+        sqlx::Error::Database(err) if err.is_unique_violation() => {
+            AppError::new(StatusCode::CONFLICT, "Username already exists")
+        }
+        sqlx::Error::RowNotFound => AppError::new(StatusCode::NOT_FOUND, "User not found"),
+        _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+    }
+}
+
 #[derive(Serialize)]
 pub struct User {
     id: i32,
@@ -53,28 +72,64 @@ impl From<UserModel> for User {
     }
 }
 
-/// "GET /users" 핸들러
-pub async fn get_user(
-    State(conn): State<Pool<Postgres>>,
-    // 이 부분은 추후에 구조체로 변경하는 것이 좋을 것 같다.
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<Vec<User>>, AppError> {
+/// 쿼리로 조건에 맞는 유저를 id 순서로 가져온다. `match=any`를 주면 조건 중 하나만 맞아도 가져온다.
+async fn find_users(
+    conn: &Pool<Postgres>,
+    params: &HashMap<String, String>,
+) -> Result<Vec<User>, AppError> {
     let Ok(id) = params.get("id").map(|s| s.parse::<i32>()).transpose() else {
         return Err(AppError::new(
             StatusCode::BAD_REQUEST,
             "ID must be an integer",
         ));
     };
-    let username = params.get("username").map(|s| s.to_string());
+    let username = params
+        .get("username")
+        .map(|username| username::normalize(username))
+        .transpose()?;
+    let match_any = match params.get("match").map(String::as_str) {
+        None | Some("all") => false,
+        Some("any") => true,
+        Some(_) => {
+            return Err(AppError::new(
+                StatusCode::BAD_REQUEST,
+                "match must be all or any",
+            ));
+        }
+    };
 
-    let Ok(user_models) = get_user_from_database(&conn, id, username).await else {
+    let Ok(user_models) = get_user_from_database(conn, id, username, match_any).await else {
         return Err(AppError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
             "Database error",
         ));
     };
 
-    Ok(Json(user_models.into_iter().map(User::from).collect()))
+    Ok(user_models.into_iter().map(User::from).collect())
+}
+
+/// "GET /users" 핸들러
+/// 조건에 맞는 유저 중 id가 가장 작은 유저를 반환한다. 맞는 유저가 없으면 404로 답한다.
+pub async fn get_user(
+    State(conn): State<Pool<Postgres>>,
+    // 이 부분은 추후에 구조체로 변경하는 것이 좋을 것 같다.
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<User>, AppError> {
+    find_users(&conn, &params)
+        .await?
+        .into_iter()
+        .next()
+        .map(Json)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))
+}
+
+/// "GET /users/list" 핸들러
+/// 조건에 맞는 유저를 id 순서로 모두 반환한다. 맞는 유저가 없으면 빈 목록을 반환한다.
+pub async fn list_users(
+    State(conn): State<Pool<Postgres>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<User>>, AppError> {
+    find_users(&conn, &params).await.map(Json)
 }
 
 #[derive(Deserialize)]
@@ -92,13 +147,11 @@ pub async fn post_user(
     if let Some(username) = user.username
         && let Some(password) = user.password
     {
+        let username = username::normalize(&username)?;
         return insert_user_to_database(&conn, &username, &password)
             .await
             .map(|user_model| Json(user_model.into()))
-            .map_err(|_| AppError {
-                code: StatusCode::INTERNAL_SERVER_ERROR,
-                message: "Database error".into(),
-            });
+            .map_err(user_write_error);
     }
 
     Err(AppError {
@@ -126,13 +179,16 @@ pub async fn put_user(
         ));
     }
 
-    update_user_from_database(&conn, id, user.username, user.password)
+    let username = user
+        .username
+        .as_deref()
+        .map(username::normalize)
+        .transpose()?;
+
+    update_user_from_database(&conn, id, username, user.password)
         .await
         .map(|user_model| Json(user_model.into()))
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => AppError::new(StatusCode::NOT_FOUND, "User not found"),
-            _ => AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database Error"),
-        })
+        .map_err(user_write_error)
 }
 
 /// "DELETE /users" 핸들러
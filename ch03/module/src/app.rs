@@ -0,0 +1,141 @@
+//! 라우터와 애플리케이션 상태
+//!
+//! 서버 바이너리와 통합 테스트가 같은 경로와 미들웨어를 쓰도록 라우터를 여기서 만든다.
+
+use std::{env, net::SocketAddr};
+
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, FromRef},
+    middleware,
+    routing::get,
+};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    api::{
+        cart::{delete_cart, get_cart, post_cart},
+        category::{
+            delete_category, get_category, get_category_by_name, get_category_tree, post_category,
+            put_category,
+        },
+        image::{get_product_image, get_product_images, post_product_images},
+        order::{get_orders, post_order, put_order},
+        product::{delete_product, get_product, post_product, put_product},
+        users::{delete_user, get_user, list_users, post_user, put_user},
+    },
+    ratelimit::{RateLimits, rate_limit},
+    storage::ImageStorage,
+};
+
+/// 기본 서버 주소
+const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// 서버 설정
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// 서버가 요청을 받을 주소
+    pub addr: SocketAddr,
+}
+
+impl Config {
+    /// 환경 변수에서 설정을 읽는다. SERVER_ADDR이 없으면 127.0.0.1:8000을 사용한다.
+    pub fn from_env() -> Self {
+        let addr = env::var("SERVER_ADDR")
+            .unwrap_or_else(|_| DEFAULT_ADDR.to_string())
+            .parse()
+            .expect("SERVER_ADDR must be a socket address");
+
+        Config { addr }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            addr: DEFAULT_ADDR.parse().unwrap(),
+        }
+    }
+}
+
+/// 핸들러와 미들웨어가 함께 쓰는 상태.
+/// 핸들러는 `State<Pool<Postgres>>`처럼 필요한 필드만 꺼내 쓴다.
+#[derive(FromRef, Clone)]
+pub struct AppState {
+    pub conn: Pool<Postgres>,
+    pub config: Config,
+    pub image_storage: ImageStorage,
+    pub rate_limits: RateLimits,
+}
+
+impl AppState {
+    /// 요청 수 제한이 없는 상태를 만든다.
+    pub fn new(conn: Pool<Postgres>, config: Config, image_storage: ImageStorage) -> Self {
+        AppState {
+            conn,
+            config,
+            image_storage,
+            rate_limits: RateLimits::default(),
+        }
+    }
+
+    pub fn with_rate_limits(mut self, rate_limits: RateLimits) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+}
+
+/// 모든 API 경로와 미들웨어를 담은 라우터를 만든다.
+///
+/// `/users`, `/category`, `/product` 경로와 요청/응답 형식은 `sea_orm_example::router`와 같다.
+/// 장바구니(`/cart`), 주문(`/orders`), 상품 이미지(`/product/{id}/images`)는 이 앱에만 있고,
+/// 관계를 함께 가져오는 `include` 쿼리는 `sea-orm-example`에만 있다.
+pub fn build_router(state: AppState) -> Router {
+    let mut images =
+        get(get_product_images)
+            .post(post_product_images)
+            .layer(DefaultBodyLimit::max(
+                state.image_storage.max_upload_bytes(),
+            ));
+    // 업로드는 디스크와 데이터베이스를 함께 쓰므로 따로 더 낮게 제한할 수 있다.
+    if let Some(limiter) = state.rate_limits.upload.clone() {
+        images = images.layer(middleware::from_fn_with_state(limiter, rate_limit));
+    }
+    let api_limit = state.rate_limits.api.clone();
+
+    let mut app = Router::new()
+        .route(
+            "/users",
+            get(get_user)
+                .post(post_user)
+                .put(put_user)
+                .delete(delete_user),
+        )
+        .route("/users/list", get(list_users))
+        .route(
+            "/category",
+            get(get_category)
+                .post(post_category)
+                .put(put_category)
+                .delete(delete_category),
+        )
+        .route("/category/{name}", get(get_category_by_name))
+        .route("/category/{name}/tree", get(get_category_tree))
+        .route(
+            "/product",
+            get(get_product)
+                .post(post_product)
+                .put(put_product)
+                .delete(delete_product),
+        )
+        .route("/product/{id}/images", images)
+        .route("/product/{id}/images/{image_id}", get(get_product_image))
+        .route("/cart", get(get_cart).post(post_cart).delete(delete_cart))
+        .route("/orders", get(get_orders).post(post_order).put(put_order))
+        .with_state(state);
+    if let Some(limiter) = api_limit {
+        app = app.layer(middleware::from_fn_with_state(limiter, rate_limit));
+    }
+
+    app
+}
@@ -0,0 +1,54 @@
+use sqlx::{Pool, Postgres, query_as};
+
+use crate::db::model::CartModel;
+
+/// 유저의 장바구니를 데이터베이스에서 가져온다.
+pub async fn get_cart_from_database(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+) -> Result<Vec<CartModel>, sqlx::Error> {
+    query_as!(
+        CartModel,
+        "SELECT * FROM cart WHERE user_id = $1 ORDER BY product_id",
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// 장바구니에 상품을 담는다.
+/// 이미 담긴 상품이면 수량을 더한다.
+pub async fn add_cart_line_to_database(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    product_id: i32,
+    quantity: i32,
+) -> Result<CartModel, sqlx::Error> {
+    query_as!(
+        CartModel,
+        r#"INSERT INTO cart (user_id, product_id, quantity) VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, product_id) DO UPDATE SET quantity = cart.quantity + EXCLUDED.quantity
+        RETURNING *"#,
+        user_id,
+        product_id,
+        quantity
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// 장바구니에서 상품을 뺀다.
+pub async fn delete_cart_line_from_database(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    product_id: i32,
+) -> Result<CartModel, sqlx::Error> {
+    query_as!(
+        CartModel,
+        "DELETE FROM cart WHERE user_id = $1 AND product_id = $2 RETURNING *",
+        user_id,
+        product_id
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -1,4 +1,4 @@
-use sqlx::{pool, query_as};
+use sqlx::{pool, query, query_as, query_scalar};
 
 use crate::db::model::CategoryModel;
 
@@ -6,11 +6,25 @@ use crate::db::model::CategoryModel;
 pub async fn get_all_categories_from_database(
     pool: &pool::Pool<sqlx::Postgres>,
 ) -> Result<Vec<CategoryModel>, sqlx::Error> {
-    query_as!(CategoryModel, "SELECT * FROM category")
+    query_as!(CategoryModel, "SELECT name, parent FROM category")
         .fetch_all(pool)
         .await
 }
 
+/// 이름이 같은 카테고리를 데이터베이스에서 가져온다.
+pub async fn get_category_from_database(
+    pool: &pool::Pool<sqlx::Postgres>,
+    name: &str,
+) -> Result<Option<CategoryModel>, sqlx::Error> {
+    query_as!(
+        CategoryModel,
+        "SELECT name, parent FROM category WHERE name = $1",
+        name
+    )
+    .fetch_optional(pool)
+    .await
+}
+
 /// 특정 문자열이 포함된 카테고리를 데이터베이스에서 가져온다.
 pub async fn get_categories_by_name_from_database(
     pool: &pool::Pool<sqlx::Postgres>,
@@ -18,7 +32,28 @@ pub async fn get_categories_by_name_from_database(
 ) -> Result<Vec<CategoryModel>, sqlx::Error> {
     query_as!(
         CategoryModel,
-        "SELECT * FROM category WHERE name ILIKE $1",
+        "SELECT name, parent FROM category WHERE name ILIKE $1",
+        name
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// 카테고리와 그 아래의 모든 하위 카테고리를 데이터베이스에서 가져온다.
+/// 카테고리가 없으면 빈 목록을 반환한다.
+pub async fn get_category_subtree_from_database(
+    pool: &pool::Pool<sqlx::Postgres>,
+    name: &str,
+) -> Result<Vec<CategoryModel>, sqlx::Error> {
+    query_as!(
+        CategoryModel,
+        r#"WITH RECURSIVE subtree AS (
+            SELECT name, parent FROM category WHERE name = $1
+            UNION ALL
+            SELECT category.name, category.parent
+            FROM category JOIN subtree ON category.parent = subtree.name
+        )
+        SELECT name AS "name!", parent FROM subtree"#,
         name
     )
     .fetch_all(pool)
@@ -29,16 +64,66 @@ pub async fn get_categories_by_name_from_database(
 pub async fn insert_category_to_database(
     pool: &pool::Pool<sqlx::Postgres>,
     name: &str,
+    parent: Option<&str>,
 ) -> Result<CategoryModel, sqlx::Error> {
     query_as!(
         CategoryModel,
-        "INSERT INTO category (name) VALUES ($1) RETURNING *",
-        name
+        "INSERT INTO category (name, parent) VALUES ($1, $2) RETURNING name, parent",
+        name,
+        parent
     )
     .fetch_one(pool)
     .await
 }
 
+/// 카테고리를 다른 부모 아래로 옮긴다. 하위 카테고리도 함께 옮겨진다.
+/// `parent`가 None이면 최상위 카테고리가 된다.
+/// 새 부모가 자기 자신이거나 하위 카테고리라서 순환이 생기면 `Ok(None)`을 반환한다.
+pub async fn move_category_in_database(
+    pool: &pool::Pool<sqlx::Postgres>,
+    name: &str,
+    parent: Option<&str>,
+) -> Result<Option<CategoryModel>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // 동시에 실행되는 이동이 서로의 검사를 통과해 순환을 만들지 않도록 막는다.
+    query!("LOCK TABLE category IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await?;
+
+    if let Some(parent) = parent {
+        let creates_cycle = query_scalar!(
+            r#"WITH RECURSIVE subtree AS (
+                SELECT name FROM category WHERE name = $1
+                UNION ALL
+                SELECT category.name FROM category JOIN subtree ON category.parent = subtree.name
+            )
+            SELECT EXISTS (SELECT 1 FROM subtree WHERE name = $2) AS "exists!""#,
+            name,
+            parent
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if creates_cycle {
+            return Ok(None);
+        }
+    }
+
+    let category = query_as!(
+        CategoryModel,
+        "UPDATE category SET parent = $1 WHERE name = $2 RETURNING name, parent",
+        parent,
+        name
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(category))
+}
+
 /// 데이터베이스에서 카테고리를 삭제한다.
 pub async fn delete_category_from_database(
     pool: &pool::Pool<sqlx::Postgres>,
@@ -46,7 +131,7 @@ pub async fn delete_category_from_database(
 ) -> Result<CategoryModel, sqlx::Error> {
     query_as!(
         CategoryModel,
-        "DELETE FROM category WHERE name = $1 RETURNING *",
+        "DELETE FROM category WHERE name = $1 RETURNING name, parent",
         name
     )
     .fetch_one(pool)
@@ -0,0 +1,55 @@
+use sqlx::{PgConnection, Pool, Postgres, query_as};
+
+use crate::db::model::ProductImageModel;
+
+/// 상품 이미지 정보를 데이터베이스에 삽입한다.
+/// 여러 장을 한꺼번에 기록할 수 있도록 트랜잭션의 연결을 받는다.
+pub async fn insert_product_image_to_database(
+    conn: &mut PgConnection,
+    product_id: i32,
+    file_name: &str,
+    content_type: &str,
+    size: i64,
+) -> Result<ProductImageModel, sqlx::Error> {
+    query_as!(
+        ProductImageModel,
+        r#"INSERT INTO product_image (product_id, file_name, content_type, size)
+        VALUES ($1, $2, $3, $4) RETURNING *"#,
+        product_id,
+        file_name,
+        content_type,
+        size
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// 상품의 이미지 목록을 데이터베이스에서 가져온다.
+pub async fn get_product_images_from_database(
+    pool: &Pool<Postgres>,
+    product_id: i32,
+) -> Result<Vec<ProductImageModel>, sqlx::Error> {
+    query_as!(
+        ProductImageModel,
+        "SELECT * FROM product_image WHERE product_id = $1 ORDER BY id",
+        product_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// 상품 이미지 하나를 데이터베이스에서 가져온다.
+pub async fn get_product_image_from_database(
+    pool: &Pool<Postgres>,
+    product_id: i32,
+    id: i32,
+) -> Result<ProductImageModel, sqlx::Error> {
+    query_as!(
+        ProductImageModel,
+        "SELECT * FROM product_image WHERE product_id = $1 AND id = $2",
+        product_id,
+        id
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -1,16 +1,32 @@
+mod cart;
 mod category;
+mod image;
 mod init;
 mod model;
+mod order;
 mod product;
 mod user;
 
+pub use cart::{add_cart_line_to_database, delete_cart_line_from_database, get_cart_from_database};
 pub use category::{
     delete_category_from_database, get_all_categories_from_database,
-    get_categories_by_name_from_database, insert_category_to_database,
+    get_categories_by_name_from_database, get_category_from_database,
+    get_category_subtree_from_database, insert_category_to_database, move_category_in_database,
+};
+pub use image::{
+    get_product_image_from_database, get_product_images_from_database,
+    insert_product_image_to_database,
 };
 pub use init::init_db;
-pub use model::{CategoryModel, ProductModel, UserModel};
-pub use product::{delete_product, insert_product, select_product, update_product};
+pub use model::{
+    CartModel, CategoryModel, OrderLineModel, OrderModel, ProductImageModel, ProductModel,
+    UserModel,
+};
+pub use order::{
+    OrderStatus, get_order_by_id_from_database, get_order_lines_from_database,
+    get_orders_from_database, place_order_to_database, update_order_status_from_database,
+};
+pub use product::{delete_product, insert_product, product_exists, select_product, update_product};
 pub use user::{
     delete_user_from_database, get_user_from_database, insert_user_to_database,
     update_user_from_database,
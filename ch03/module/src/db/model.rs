@@ -6,6 +6,7 @@ pub struct UserModel {
 
 pub struct CategoryModel {
     pub name: String,
+    pub parent: Option<String>,
 }
 
 pub struct ProductModel {
@@ -14,3 +15,32 @@ pub struct ProductModel {
     pub price: i32,
     pub category: String,
 }
+
+pub struct CartModel {
+    pub user_id: i32,
+    pub product_id: i32,
+    pub quantity: i32,
+}
+
+pub struct OrderModel {
+    pub id: i32,
+    pub user_id: i32,
+    pub status: String,
+}
+
+pub struct OrderLineModel {
+    pub id: i32,
+    pub order_id: i32,
+    pub product_id: i32,
+    pub title: String,
+    pub price: i32,
+    pub quantity: i32,
+}
+
+pub struct ProductImageModel {
+    pub id: i32,
+    pub product_id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+}
@@ -0,0 +1,166 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, query_as};
+
+use crate::db::model::{OrderLineModel, OrderModel};
+
+/// 주문 상태
+/// pending -> paid -> shipped 순서로 진행되며, 배송 전까지는 취소할 수 있다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Shipped,
+    Cancelled,
+}
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::Paid => "paid",
+            OrderStatus::Shipped => "shipped",
+            OrderStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// 현재 상태에서 `next` 상태로 바꿀 수 있는지 확인한다.
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        matches!(
+            (self, next),
+            (OrderStatus::Pending, OrderStatus::Paid)
+                | (OrderStatus::Pending, OrderStatus::Cancelled)
+                | (OrderStatus::Paid, OrderStatus::Shipped)
+                | (OrderStatus::Paid, OrderStatus::Cancelled)
+        )
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(OrderStatus::Pending),
+            "paid" => Ok(OrderStatus::Paid),
+            "shipped" => Ok(OrderStatus::Shipped),
+            "cancelled" => Ok(OrderStatus::Cancelled),
+            _ => Err(format!("Unknown order status: {s}")),
+        }
+    }
+}
+
+/// 장바구니의 상품으로 주문을 만든다.
+/// 주문 시점의 상품 이름과 가격을 주문 항목에 복사하고 장바구니를 비운다.
+/// 모든 작업은 하나의 트랜잭션 안에서 실행된다.
+/// 장바구니가 비어 있으면 `sqlx::Error::RowNotFound`를 반환한다.
+///
+/// 장바구니 행을 지우면서 돌려받은 행으로 주문 항목을 만든다.
+/// 같은 유저의 주문이 동시에 들어오면 나중 트랜잭션은 행 잠금을 기다렸다가 이미 지워진 장바구니를 보므로
+/// 같은 장바구니로 주문이 두 번 만들어지지 않는다.
+pub async fn place_order_to_database(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+) -> Result<(OrderModel, Vec<OrderLineModel>), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let order = query_as!(
+        OrderModel,
+        "INSERT INTO orders (user_id, status) VALUES ($1, $2) RETURNING *",
+        user_id,
+        OrderStatus::Pending.as_str()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let lines = query_as!(
+        OrderLineModel,
+        r#"WITH taken AS (
+            DELETE FROM cart WHERE user_id = $2 RETURNING product_id, quantity
+        )
+        INSERT INTO order_line (order_id, product_id, title, price, quantity)
+        SELECT $1, product.id, product.title, product.price, taken.quantity
+        FROM taken JOIN product ON product.id = taken.product_id
+        ORDER BY product.id
+        RETURNING *"#,
+        order.id,
+        user_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if lines.is_empty() {
+        tx.rollback().await?;
+        return Err(sqlx::Error::RowNotFound);
+    }
+
+    tx.commit().await?;
+
+    Ok((order, lines))
+}
+
+/// 유저의 주문 목록을 가져온다.
+pub async fn get_orders_from_database(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+) -> Result<Vec<OrderModel>, sqlx::Error> {
+    query_as!(
+        OrderModel,
+        "SELECT * FROM orders WHERE user_id = $1 ORDER BY id",
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// id로 주문 하나를 가져온다.
+pub async fn get_order_by_id_from_database(
+    pool: &Pool<Postgres>,
+    id: i32,
+) -> Result<OrderModel, sqlx::Error> {
+    query_as!(OrderModel, "SELECT * FROM orders WHERE id = $1", id)
+        .fetch_one(pool)
+        .await
+}
+
+/// 여러 주문의 주문 항목을 한 번에 가져온다.
+pub async fn get_order_lines_from_database(
+    pool: &Pool<Postgres>,
+    order_ids: &[i32],
+) -> Result<Vec<OrderLineModel>, sqlx::Error> {
+    query_as!(
+        OrderLineModel,
+        "SELECT * FROM order_line WHERE order_id = ANY($1) ORDER BY order_id, id",
+        order_ids
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// 주문 상태를 바꾼다.
+/// 주문의 상태가 `current`일 때만 바꾸므로 그 사이에 상태가 바뀌었다면
+/// `sqlx::Error::RowNotFound`를 반환한다.
+pub async fn update_order_status_from_database(
+    pool: &Pool<Postgres>,
+    id: i32,
+    current: OrderStatus,
+    next: OrderStatus,
+) -> Result<OrderModel, sqlx::Error> {
+    query_as!(
+        OrderModel,
+        "UPDATE orders SET status = $1 WHERE id = $2 AND status = $3 RETURNING *",
+        next.as_str(),
+        id,
+        current.as_str()
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -1,4 +1,4 @@
-use sqlx::{Pool, Postgres, QueryBuilder, Row, postgres::PgQueryResult, query, query_as};
+use sqlx::{Pool, Postgres, QueryBuilder, Row, query, query_as, query_scalar};
 
 use crate::db::model::ProductModel;
 
@@ -8,37 +8,44 @@ pub async fn select_product(
     title: Option<String>,
     price: Option<i32>,
     category: Option<String>,
+    include_subcategories: bool,
 ) -> Result<Vec<ProductModel>, sqlx::Error> {
-    let mut query_builder = QueryBuilder::<Postgres>::new("SELECT * from product");
+    let mut query_builder =
+        QueryBuilder::<Postgres>::new("SELECT id, title, price, category FROM product");
 
     if id.is_some() || title.is_some() || price.is_some() || category.is_some() {
         query_builder.push(" WHERE ");
     }
 
-    let mut first = true;
-
-    let mut add_condition = |column: &str, value: String| {
-        if first {
-            first = false;
-        } else {
-            query_builder.push(" AND ");
-        }
-        query_builder
-            .push(format_args!("{column} = "))
-            .push_bind(value);
-    };
+    // 조건 사이에 " AND "를 넣는다. 값은 컬럼 타입 그대로 바인딩해야 정수 컬럼과 비교할 수 있다.
+    let mut conditions = query_builder.separated(" AND ");
 
     if let Some(id) = id {
-        add_condition("id", id.to_string())
+        conditions.push("id = ").push_bind_unseparated(id);
     }
     if let Some(title) = title {
-        add_condition("title", title);
+        conditions.push("title = ").push_bind_unseparated(title);
     }
     if let Some(price) = price {
-        add_condition("price", price.to_string());
+        conditions.push("price = ").push_bind_unseparated(price);
     }
     if let Some(category) = category {
-        add_condition("category", category);
+        if include_subcategories {
+            // 재귀 CTE로 하위 카테고리를 모두 찾아서 그 안에 속한 상품을 가져온다.
+            conditions
+                .push(
+                    "category IN (WITH RECURSIVE subtree AS (SELECT name FROM category WHERE name = ",
+                )
+                .push_bind_unseparated(category)
+                .push_unseparated(
+                    " UNION ALL SELECT category.name FROM category \
+                    JOIN subtree ON category.parent = subtree.name) SELECT name FROM subtree)",
+                );
+        } else {
+            conditions
+                .push("category = ")
+                .push_bind_unseparated(category);
+        }
     }
 
     let product_query = query_builder.build();
@@ -68,7 +75,7 @@ pub async fn insert_product(
 ) -> Result<ProductModel, sqlx::Error> {
     let product_model = query_as!(
         ProductModel,
-        "INSERT INTO product (title, price, category) VALUES ($1, $2, $3) RETURNING *",
+        "INSERT INTO product (title, price, category) VALUES ($1, $2, $3) RETURNING id, title, price, category",
         title,
         price,
         category
@@ -86,9 +93,13 @@ pub async fn update_product(
     price: Option<i32>,
     category: Option<&str>,
 ) -> Result<ProductModel, sqlx::Error> {
-    let product_id = query_as!(ProductModel, "SELECT * FROM product WHERE id = $1", id)
-        .fetch_one(executor)
-        .await?;
+    let product_id = query_as!(
+        ProductModel,
+        "SELECT id, title, price, category FROM product WHERE id = $1",
+        id
+    )
+    .fetch_one(executor)
+    .await?;
 
     let title = title.unwrap_or(&product_id.title);
     let price = price.unwrap_or(product_id.price);
@@ -96,7 +107,7 @@ pub async fn update_product(
 
     let product_model = query_as!(
         ProductModel,
-        "UPDATE product SET title = $1, price = $2, category = $3 WHERE id = $4 RETURNING *",
+        "UPDATE product SET title = $1, price = $2, category = $3 WHERE id = $4 RETURNING id, title, price, category",
         title,
         price,
         category,
@@ -108,11 +119,41 @@ pub async fn update_product(
     Ok(product_model)
 }
 
+/// 상품과 상품 이미지 기록을 지우고 지운 이미지의 파일 이름을 반환한다.
+/// 상품이 없으면 `sqlx::Error::RowNotFound`를 반환한다.
+/// 파일은 지우지 않으므로 호출한 쪽에서 지워야 한다.
 pub async fn delete_product(
     executor: &Pool<Postgres>,
     id: i32,
-) -> Result<PgQueryResult, sqlx::Error> {
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut tx = executor.begin().await?;
+
+    // 상품을 먼저 잠가서 지우는 동안 새 이미지가 기록되지 않게 한다.
+    query_scalar!("SELECT id FROM product WHERE id = $1 FOR UPDATE", id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let file_names = query_scalar!(
+        "DELETE FROM product_image WHERE product_id = $1 RETURNING file_name",
+        id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
     query!("DELETE FROM product WHERE id = $1", id)
-        .execute(executor)
-        .await
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(file_names)
+}
+
+/// 상품이 있는지 확인한다.
+pub async fn product_exists(executor: &Pool<Postgres>, id: i32) -> Result<bool, sqlx::Error> {
+    query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM product WHERE id = $1) AS "exists!""#,
+        id
+    )
+    .fetch_one(executor)
+    .await
 }
@@ -3,45 +3,61 @@ use sqlx::{Pool, Postgres, query_as};
 use crate::db::UserModel;
 
 /// DB에서 유저를 가져오는 함수
-/// id와 username이 None이면 모든 유저를 가져온다.
+/// id와 username이 None이면 모든 유저를 가져온다. 둘 다 있으면 `match_any`가 true일 때 둘 중 하나만 맞아도 가져온다.
+/// username은 `username::normalize`으로 바꾼 값이어야 한다. 대소문자를 구분하지 않고 비교한다.
 pub async fn get_user_from_database(
     pool: &Pool<Postgres>,
     id: Option<i32>,
     username: Option<String>,
+    match_any: bool,
 ) -> Result<Vec<UserModel>, sqlx::Error> {
-    let result = match (id, username) {
-        // Querybuilder를 사용하는 방법도 있지만 가지가 많지 않으므로 직접 쿼리를 작성한다.
-        (Some(id), Some(username)) => {
-            query_as!(
+    let result =
+        match (id, username) {
+            // Querybuilder를 사용하는 방법도 있지만 가지가 많지 않으므로 직접 쿼리를 작성한다.
+            (Some(id), Some(username)) if match_any => query_as!(
                 UserModel,
-                "SELECT * FROM users WHERE id = $1 AND username = $2",
+                "SELECT id, username, password FROM users WHERE id = $1 OR lower(username) = $2 ORDER BY id",
                 id,
                 username
             )
             .fetch_all(pool)
-            .await?
-        }
-        (Some(id), None) => {
-            query_as!(UserModel, "SELECT * FROM users WHERE id = $1", id)
-                .fetch_all(pool)
-                .await?
-        }
-
-        (None, Some(username)) => {
-            query_as!(
+            .await?,
+            (Some(id), Some(username)) => query_as!(
                 UserModel,
-                "SELECT * FROM users WHERE username = $1",
+                "SELECT id, username, password FROM users WHERE id = $1 AND lower(username) = $2 ORDER BY id",
+                id,
                 username
             )
             .fetch_all(pool)
-            .await?
-        }
-        (None, None) => {
-            query_as!(UserModel, "SELECT * FROM users")
+            .await?,
+            (Some(id), None) => {
+                query_as!(
+                    UserModel,
+                    "SELECT id, username, password FROM users WHERE id = $1",
+                    id
+                )
                 .fetch_all(pool)
                 .await?
-        }
-    };
+            }
+
+            (None, Some(username)) => {
+                query_as!(
+                    UserModel,
+                    "SELECT id, username, password FROM users WHERE lower(username) = $1",
+                    username
+                )
+                .fetch_all(pool)
+                .await?
+            }
+            (None, None) => {
+                query_as!(
+                    UserModel,
+                    "SELECT id, username, password FROM users ORDER BY id"
+                )
+                .fetch_all(pool)
+                .await?
+            }
+        };
 
     Ok(result)
 }
@@ -54,7 +70,7 @@ pub async fn insert_user_to_database(
 ) -> Result<UserModel, sqlx::Error> {
     query_as!(
         UserModel,
-        r#"INSERT INTO users (username, password) VALUES ($1, $2) RETURNING *"#,
+        r#"INSERT INTO users (username, password) VALUES ($1, $2) RETURNING id, username, password"#,
         username,
         password
     )
@@ -69,7 +85,7 @@ pub async fn delete_user_from_database(
 ) -> Result<UserModel, sqlx::Error> {
     query_as!(
         UserModel,
-        r#"DELETE FROM users WHERE id = $1 RETURNING *"#,
+        r#"DELETE FROM users WHERE id = $1 RETURNING id, username, password"#,
         id
     )
     .fetch_one(pool)
@@ -88,7 +104,7 @@ pub async fn update_user_from_database(
         (Some(username), Some(password)) => {
             query_as!(
                 UserModel,
-                r#"UPDATE users SET username = $1, password = $2 WHERE id = $3 RETURNING *"#,
+                r#"UPDATE users SET username = $1, password = $2 WHERE id = $3 RETURNING id, username, password"#,
                 username,
                 password,
                 id
@@ -99,7 +115,7 @@ pub async fn update_user_from_database(
         (Some(username), None) => {
             query_as!(
                 UserModel,
-                r#"UPDATE users SET username = $1 WHERE id = $2 RETURNING *"#,
+                r#"UPDATE users SET username = $1 WHERE id = $2 RETURNING id, username, password"#,
                 username,
                 id
             )
@@ -109,7 +125,7 @@ pub async fn update_user_from_database(
         (None, Some(password)) => {
             query_as!(
                 UserModel,
-                r#"UPDATE users SET password = $1 WHERE id = $2 RETURNING *"#,
+                r#"UPDATE users SET password = $1 WHERE id = $2 RETURNING id, username, password"#,
                 password,
                 id
             )
@@ -117,7 +133,7 @@ pub async fn update_user_from_database(
             .await
         }
         (None, None) => {
-            query_as!(UserModel, r#"SELECT * FROM users WHERE id = $1"#, id)
+            query_as!(UserModel, r#"SELECT id, username, password FROM users WHERE id = $1"#, id)
                 .fetch_one(pool)
                 .await
         }
@@ -1,2 +1,5 @@
 pub mod api;
+pub mod app;
 pub mod db;
+pub mod ratelimit;
+pub mod storage;
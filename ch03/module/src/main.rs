@@ -1,30 +1,43 @@
-use axum::{Router, routing::get};
+use std::{net::SocketAddr, sync::Arc};
+
 use module::{
-    api::{
-        category::{delete_category, get_category, post_category},
-        users::{delete_user, get_user, post_user},
-    },
+    app::{AppState, Config, build_router},
     db::init_db,
+    ratelimit::{MemoryStore, RateLimits},
+    storage::ImageStorage,
 };
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    let config = Config::from_env();
     let conn = init_db().await;
+    let image_storage = ImageStorage::from_env();
+    image_storage
+        .init()
+        .await
+        .expect("Failed to create image storage directory");
 
-    let app = Router::new()
-        .route("/users", get(get_user).post(post_user).delete(delete_user))
-        .route(
-            "/category",
-            get(get_category)
-                .post(post_category)
-                .delete(delete_category),
-        )
-        .with_state(conn);
+    let addr = config.addr;
+    let state = AppState::new(conn, config, image_storage)
+        .with_rate_limits(RateLimits::from_env(Arc::new(MemoryStore::default())));
+    let app = build_router(state);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:8000")
+    let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind TcpListener");
 
-    axum::serve(listener, app).await.unwrap()
+    // 요청 수 제한에 쓸 클라이언트 주소를 미들웨어에서 알 수 있게 한다.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap()
 }
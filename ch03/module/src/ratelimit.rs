@@ -0,0 +1,30 @@
+//! 이 앱의 요청 수 제한 설정
+//!
+//! 토큰 버킷과 미들웨어는 `rate-limiter` 크레이트에 있고, 여기서는 어떤 경로에 어떤 제한을 걸지만 정한다.
+
+use std::sync::Arc;
+
+pub use rate_limiter::{
+    AuthenticatedUser, BoxFuture, Decision, KeyBy, MemoryStore, Quota, RateLimitStore, RateLimiter,
+    rate_limit,
+};
+
+/// 서버에 거는 요청 수 제한. None이면 제한하지 않는다.
+#[derive(Clone, Default)]
+pub struct RateLimits {
+    /// 모든 요청에 거는 제한
+    pub api: Option<RateLimiter>,
+    /// 이미지 업로드에만 따로 거는 제한
+    pub upload: Option<RateLimiter>,
+}
+
+impl RateLimits {
+    /// `RATE_LIMIT_*`과 `UPLOAD_RATE_LIMIT_*` 환경 변수에서 설정을 읽는다.
+    /// 두 제한이 저장소 하나를 함께 쓰고, 버킷은 이름으로 나눈다.
+    pub fn from_env(store: Arc<dyn RateLimitStore>) -> Self {
+        RateLimits {
+            api: RateLimiter::from_env("api", "RATE_LIMIT", store.clone()),
+            upload: RateLimiter::from_env("upload", "UPLOAD_RATE_LIMIT", store),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::{env, io, path::PathBuf};
+
+/// 이미지 하나의 기본 최대 크기 (5MB)
+const DEFAULT_MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+/// 업로드 요청 하나의 기본 최대 크기 (20MB)
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 20 * 1024 * 1024;
+/// 이미지 형식을 알아내는 데 필요한 최대 바이트 수
+pub const SNIFF_LEN: usize = 12;
+
+/// 상품 이미지를 저장하는 로컬 디스크 저장소
+#[derive(Clone)]
+pub struct ImageStorage {
+    dir: PathBuf,
+    max_image_bytes: usize,
+    max_upload_bytes: usize,
+}
+
+impl ImageStorage {
+    pub fn new(dir: impl Into<PathBuf>, max_image_bytes: usize, max_upload_bytes: usize) -> Self {
+        ImageStorage {
+            dir: dir.into(),
+            max_image_bytes,
+            max_upload_bytes,
+        }
+    }
+
+    /// 환경 변수에서 설정을 읽는다.
+    /// IMAGE_STORAGE_DIR, IMAGE_MAX_BYTES, IMAGE_MAX_UPLOAD_BYTES가 없으면 기본값을 사용한다.
+    pub fn from_env() -> Self {
+        let dir = env::var("IMAGE_STORAGE_DIR").unwrap_or_else(|_| "images".to_string());
+        let parse = |key: &str, default: usize| {
+            env::var(key)
+                .ok()
+                .map(|value| {
+                    value
+                        .parse()
+                        .unwrap_or_else(|_| panic!("{key} must be a positive integer"))
+                })
+                .unwrap_or(default)
+        };
+
+        ImageStorage::new(
+            dir,
+            parse("IMAGE_MAX_BYTES", DEFAULT_MAX_IMAGE_BYTES),
+            parse("IMAGE_MAX_UPLOAD_BYTES", DEFAULT_MAX_UPLOAD_BYTES),
+        )
+    }
+
+    /// 저장소 디렉터리가 없으면 만든다.
+    pub async fn init(&self) -> io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await
+    }
+
+    pub fn max_image_bytes(&self) -> usize {
+        self.max_image_bytes
+    }
+
+    pub fn max_upload_bytes(&self) -> usize {
+        self.max_upload_bytes
+    }
+
+    /// 저장된 파일의 경로를 반환한다.
+    pub fn path(&self, file_name: &str) -> PathBuf {
+        self.dir.join(file_name)
+    }
+
+    /// 저장된 파일을 지운다. 이미 없는 파일은 건너뛰고, 지우지 못한 파일은 로그만 남긴다.
+    pub async fn remove_files(&self, file_names: &[String]) {
+        for file_name in file_names {
+            match tokio::fs::remove_file(self.path(file_name)).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => tracing::warn!("이미지 파일 {file_name}을 지우지 못했습니다: {err}"),
+            }
+        }
+    }
+}
+
+/// 파일의 앞부분을 보고 이미지 형식을 알아낸다.
+/// 클라이언트가 보낸 Content-Type은 믿지 않는다.
+pub fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
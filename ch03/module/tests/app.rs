@@ -0,0 +1,65 @@
+//! `build_router`가 상태에 담긴 미들웨어를 거는지 확인하는 테스트
+
+mod common;
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use module::ratelimit::{KeyBy, MemoryStore, Quota, RateLimiter, RateLimits};
+use tower::ServiceExt;
+
+use common::TestApp;
+
+#[tokio::test]
+async fn build_router_applies_api_rate_limit() {
+    let limiter = RateLimiter::new(
+        "api",
+        Quota::per_minute(2),
+        KeyBy::Ip,
+        Arc::new(MemoryStore::default()),
+    );
+    let app = TestApp::spawn_with_rate_limits(RateLimits {
+        api: Some(limiter),
+        upload: None,
+    })
+    .await;
+
+    for _ in 0..2 {
+        let (status, _) = app.get("/category").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, body) = app.get("/category").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body, "Too many requests");
+}
+
+#[tokio::test]
+async fn unverified_authorization_does_not_bypass_user_rate_limit() {
+    let limiter = RateLimiter::new(
+        "api",
+        Quota::per_minute(1),
+        KeyBy::User,
+        Arc::new(MemoryStore::default()),
+    );
+    let app = TestApp::spawn_with_rate_limits(RateLimits {
+        api: Some(limiter),
+        upload: None,
+    })
+    .await;
+
+    // 인증 미들웨어가 확인하지 않은 헤더는 요청마다 바꿔도 같은 IP 버킷을 쓴다.
+    let mut statuses = Vec::new();
+    for token in ["made-up-1", "made-up-2"] {
+        let request = Request::builder()
+            .uri("/category")
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.router.clone().oneshot(request).await.unwrap();
+        statuses.push(response.status());
+    }
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::TOO_MANY_REQUESTS]);
+}
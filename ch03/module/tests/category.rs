@@ -0,0 +1,168 @@
+//! `/category` 핸들러 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+/// Electronics > Computer > Laptop, Electronics > Phone 트리를 만든다.
+async fn create_tree(app: &TestApp) {
+    for (name, parent) in [
+        ("Electronics", None),
+        ("Computer", Some("Electronics")),
+        ("Laptop", Some("Computer")),
+        ("Phone", Some("Electronics")),
+    ] {
+        let (status, _) = app
+            .post("/category", json!({ "name": name, "parent": parent }))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{name}");
+    }
+}
+
+fn names(categories: &Value) -> Vec<&str> {
+    let mut names = categories
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|category| category["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn post_and_get_category() {
+    let app = TestApp::spawn().await;
+    create_tree(&app).await;
+
+    let (status, categories) = app.get("/category").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        names(&categories),
+        ["Computer", "Electronics", "Laptop", "Phone"]
+    );
+
+    // name은 ILIKE 패턴으로 찾는다.
+    let (_, categories) = app.get("/category?name=%25o%25").await;
+    assert_eq!(
+        names(&categories),
+        ["Computer", "Electronics", "Laptop", "Phone"]
+    );
+    let (_, categories) = app.get("/category?name=laptop").await;
+    assert_eq!(
+        categories,
+        json!([{ "name": "Laptop", "parent": "Computer" }])
+    );
+
+    let (status, _) = app
+        .post("/category", json!({ "name": "Tablet", "parent": "Nope" }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, category) = app.get("/category/Laptop").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(category, json!({ "name": "Laptop", "parent": "Computer" }));
+
+    let (status, _) = app.get("/category/laptop").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn put_category_moves_subtree_and_rejects_cycles() {
+    let app = TestApp::spawn().await;
+    create_tree(&app).await;
+
+    let (status, category) = app
+        .put(
+            "/category",
+            json!({ "name": "Computer", "parent": "Phone" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(category, json!({ "name": "Computer", "parent": "Phone" }));
+
+    let (status, _) = app
+        .put(
+            "/category",
+            json!({ "name": "Electronics", "parent": "Laptop" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = app
+        .put("/category", json!({ "name": "Phone", "parent": "Phone" }))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = app
+        .put("/category", json!({ "name": "Nope", "parent": null }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .put("/category", json!({ "name": "Phone", "parent": "Nope" }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // 부모를 지우면 최상위 카테고리가 된다.
+    let (status, category) = app
+        .put("/category", json!({ "name": "Computer", "parent": null }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(category, json!({ "name": "Computer", "parent": null }));
+}
+
+#[tokio::test]
+async fn get_category_tree_returns_nested_children() {
+    let app = TestApp::spawn().await;
+    create_tree(&app).await;
+
+    let (status, tree) = app.get("/category/Electronics/tree").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        tree,
+        json!({
+            "name": "Electronics",
+            "children": [
+                {
+                    "name": "Computer",
+                    "children": [{ "name": "Laptop", "children": [] }]
+                },
+                { "name": "Phone", "children": [] }
+            ]
+        })
+    );
+
+    let (status, _) = app.get("/category/Nope/tree").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn delete_category_refuses_while_in_use() {
+    let app = TestApp::spawn().await;
+    create_tree(&app).await;
+    app.post(
+        "/product",
+        json!({ "title": "Galaxy", "price": 1000, "category": "Phone" }),
+    )
+    .await;
+
+    let (status, _) = app.delete("/category?name=Computer").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = app.delete("/category?name=Phone").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = app.delete("/category?name=Laptop").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "Deleted");
+
+    let (status, _) = app.delete("/category?name=Laptop").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.delete("/category").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,156 @@
+//! 통합 테스트 공용 도구
+//!
+//! 테스트마다 로컬 Postgres(DATABASE_URL)에 새 데이터베이스를 만들고 모든 마이그레이션을 적용한 뒤
+//! `module::app::build_router`로 서버와 같은 라우터를 만들어서 `oneshot`으로 요청을 보낸다.
+//! `TestApp`을 버릴 때 데이터베이스와 이미지 디렉터리를 지우므로 테스트가 패닉해도 남지 않는다.
+
+// 테스트 파일마다 따로 컴파일되므로 쓰지 않는 도우미가 생긴다.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use migration::testing::TestDatabase;
+use serde_json::Value;
+use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use tower::ServiceExt;
+
+use module::{
+    app::{AppState, Config, build_router},
+    ratelimit::RateLimits,
+    storage::ImageStorage,
+};
+
+pub struct TestApp {
+    pub router: Router,
+    pub pool: Pool<Postgres>,
+    pub image_dir: PathBuf,
+    /// 버릴 때 데이터베이스를 지운다.
+    database: TestDatabase,
+}
+
+impl TestApp {
+    /// 새 데이터베이스에 마이그레이션을 적용하고 라우터를 만든다.
+    pub async fn spawn() -> Self {
+        Self::spawn_with_rate_limits(RateLimits::default()).await
+    }
+
+    /// 요청 수 제한을 건 라우터를 만든다.
+    pub async fn spawn_with_rate_limits(rate_limits: RateLimits) -> Self {
+        let database = TestDatabase::migrated("module_test").await;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(database.url())
+            .await
+            .unwrap();
+        let image_dir = std::env::temp_dir().join(database.name());
+        let image_storage = ImageStorage::new(image_dir.clone(), 1024 * 1024, 4 * 1024 * 1024);
+        image_storage.init().await.unwrap();
+        let state = AppState::new(pool.clone(), Config::default(), image_storage)
+            .with_rate_limits(rate_limits);
+        let router = build_router(state);
+
+        TestApp {
+            router,
+            pool,
+            image_dir,
+            database,
+        }
+    }
+
+    /// 요청을 보내고 상태 코드와 JSON 본문을 반환한다. 본문이 비어 있으면 `Value::Null`이다.
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+        self.send(request).await
+    }
+
+    /// 요청을 보내고 상태 코드와 JSON 본문을 반환한다.
+    pub async fn send(&self, request: Request<Body>) -> (StatusCode, Value) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+        };
+
+        (status, body)
+    }
+
+    /// 파일마다 (파일 이름, 내용)을 multipart/form-data로 보내고 상태 코드와 JSON 본문을 반환한다.
+    pub async fn post_files(&self, uri: &str, files: &[(&str, &[u8])]) -> (StatusCode, Value) {
+        const BOUNDARY: &str = "test-boundary";
+        let mut body = Vec::new();
+        for (file_name, content) in files {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"image\"; \
+                     filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(content);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        self.send(request).await
+    }
+
+    /// 이미지 디렉터리에 남은 파일 수
+    pub fn image_file_count(&self) -> usize {
+        std::fs::read_dir(&self.image_dir).unwrap().count()
+    }
+
+    pub async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        self.request(Method::GET, uri, None).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::POST, uri, Some(body)).await
+    }
+
+    pub async fn put(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::PUT, uri, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str) -> (StatusCode, Value) {
+        self.request(Method::DELETE, uri, None).await
+    }
+}
+
+impl Drop for TestApp {
+    /// 이미지 디렉터리를 지운다. 데이터베이스는 `TestDatabase`가 지운다.
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.image_dir);
+    }
+}
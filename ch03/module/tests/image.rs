@@ -0,0 +1,92 @@
+//! `/product/{id}/images` 핸들러 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+const GIF: &[u8] = b"GIF89a\x01\0\x01\0\0\0\0";
+
+/// 카테고리와 상품을 만들고 상품을 반환한다.
+async fn create_product(app: &TestApp) -> Value {
+    app.post(
+        "/category",
+        json!({ "name": "Electronics", "parent": null }),
+    )
+    .await;
+    let (_, product) = app
+        .post(
+            "/product",
+            json!({ "title": "Radio", "price": 100, "category": "Electronics" }),
+        )
+        .await;
+    product
+}
+
+#[tokio::test]
+async fn upload_stores_every_image() {
+    let app = TestApp::spawn().await;
+    let id = &create_product(&app).await["id"];
+    let uri = format!("/product/{id}/images");
+
+    let (status, images) = app
+        .post_files(&uri, &[("a.png", PNG), ("b.gif", GIF)])
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(images[0]["content_type"], "image/png");
+    assert_eq!(images[1]["content_type"], "image/gif");
+    assert_eq!(app.image_file_count(), 2);
+
+    let (_, listed) = app.get(&uri).await;
+    assert_eq!(listed, images);
+}
+
+#[tokio::test]
+async fn failed_upload_rolls_back_the_whole_batch() {
+    let app = TestApp::spawn().await;
+    let id = &create_product(&app).await["id"];
+    let uri = format!("/product/{id}/images");
+
+    // 두 번째 파일이 이미지가 아니면 첫 번째 파일도 남기지 않는다.
+    let (status, _) = app
+        .post_files(&uri, &[("a.png", PNG), ("b.txt", b"not an image")])
+        .await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let (_, images) = app.get(&uri).await;
+    assert_eq!(images, json!([]));
+    assert_eq!(app.image_file_count(), 0);
+
+    // 크기 제한을 넘어도 마찬가지다.
+    let too_large = [PNG, &vec![0; 1024 * 1024]].concat();
+    let (status, _) = app
+        .post_files(&uri, &[("a.png", PNG), ("b.png", &too_large)])
+        .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(app.image_file_count(), 0);
+
+    // 없는 상품이면 파일을 남기지 않는다.
+    let (status, _) = app
+        .post_files("/product/9999/images", &[("a.png", PNG)])
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(app.image_file_count(), 0);
+}
+
+#[tokio::test]
+async fn delete_product_removes_image_files() {
+    let app = TestApp::spawn().await;
+    let id = &create_product(&app).await["id"];
+    let uri = format!("/product/{id}/images");
+    app.post_files(&uri, &[("a.png", PNG), ("b.gif", GIF)])
+        .await;
+    assert_eq!(app.image_file_count(), 2);
+
+    let (status, _) = app.delete(&format!("/product?id={id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(app.image_file_count(), 0);
+    let (_, images) = app.get(&uri).await;
+    assert_eq!(images, json!([]));
+}
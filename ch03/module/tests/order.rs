@@ -0,0 +1,212 @@
+//! `/cart`, `/orders` 핸들러와 주문 상태 변경 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use module::db::{OrderStatus, place_order_to_database, update_order_status_from_database};
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+/// 유저와 상품 두 개를 만들고 (유저 id, 상품 id 목록)을 반환한다.
+async fn create_user_and_products(app: &TestApp) -> (Value, Vec<Value>) {
+    let (_, user) = app
+        .post("/users", json!({ "username": "alice", "password": "pw" }))
+        .await;
+    app.post(
+        "/category",
+        json!({ "name": "Electronics", "parent": null }),
+    )
+    .await;
+
+    let mut product_ids = Vec::new();
+    for (title, price) in [("Radio", 100), ("Lamp", 30)] {
+        let (_, product) = app
+            .post(
+                "/product",
+                json!({ "title": title, "price": price, "category": "Electronics" }),
+            )
+            .await;
+        product_ids.push(product["id"].clone());
+    }
+    (user["id"].clone(), product_ids)
+}
+
+/// 장바구니에 상품을 담고 주문을 만든다.
+async fn place_order(app: &TestApp, user_id: &Value, product_ids: &[Value]) -> Value {
+    for product_id in product_ids {
+        let (status, _) = app
+            .post(
+                "/cart",
+                json!({ "user_id": user_id, "product_id": product_id, "quantity": 2 }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, order) = app.post("/orders", json!({ "user_id": user_id })).await;
+    assert_eq!(status, StatusCode::OK);
+    order
+}
+
+#[test]
+fn order_status_allows_only_forward_moves_and_cancel_before_shipping() {
+    use OrderStatus::*;
+
+    let all = [Pending, Paid, Shipped, Cancelled];
+    let allowed = [
+        (Pending, Paid),
+        (Pending, Cancelled),
+        (Paid, Shipped),
+        (Paid, Cancelled),
+    ];
+    for current in all {
+        for next in all {
+            assert_eq!(
+                current.can_transition_to(next),
+                allowed.contains(&(current, next)),
+                "{current} -> {next}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn post_order_moves_cart_into_order() {
+    let app = TestApp::spawn().await;
+    let (user_id, product_ids) = create_user_and_products(&app).await;
+
+    let order = place_order(&app, &user_id, &product_ids).await;
+    assert_eq!(order["status"], "pending");
+    assert_eq!(order["total"], 2 * 100 + 2 * 30);
+    assert_eq!(order["lines"].as_array().unwrap().len(), 2);
+
+    let (_, cart) = app.get(&format!("/cart?user_id={user_id}")).await;
+    assert_eq!(cart, json!([]));
+    let (_, orders) = app.get(&format!("/orders?user_id={user_id}")).await;
+    assert_eq!(orders, json!([order]));
+
+    let (status, _) = app.post("/orders", json!({ "user_id": user_id })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn concurrent_orders_take_the_cart_once() {
+    let app = TestApp::spawn().await;
+    let (user_id, product_ids) = create_user_and_products(&app).await;
+    for product_id in &product_ids {
+        app.post(
+            "/cart",
+            json!({ "user_id": user_id, "product_id": product_id, "quantity": 1 }),
+        )
+        .await;
+    }
+
+    let user_id = user_id.as_i64().unwrap() as i32;
+    let results =
+        futures::future::join_all((0..4).map(|_| place_order_to_database(&app.pool, user_id)))
+            .await;
+
+    let placed = results.iter().filter(|result| result.is_ok()).count();
+    assert_eq!(placed, 1);
+    for result in &results {
+        assert!(matches!(result, Ok(_) | Err(sqlx::Error::RowNotFound)));
+    }
+    let (_, orders) = app.get(&format!("/orders?user_id={user_id}")).await;
+    assert_eq!(orders.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn unknown_user_is_404_not_500() {
+    let app = TestApp::spawn().await;
+    let (_, product_ids) = create_user_and_products(&app).await;
+
+    let (status, body) = app.post("/orders", json!({ "user_id": 9999 })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, "User not found");
+
+    let (status, _) = app
+        .post(
+            "/cart",
+            json!({ "user_id": 9999, "product_id": product_ids[0] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn put_order_follows_the_state_machine() {
+    let app = TestApp::spawn().await;
+    let (user_id, product_ids) = create_user_and_products(&app).await;
+    let id = place_order(&app, &user_id, &product_ids).await["id"].clone();
+
+    for status in ["paid", "shipped"] {
+        let (code, order) = app
+            .put("/orders", json!({ "id": id, "status": status }))
+            .await;
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(order["status"], status);
+    }
+
+    // 배송된 주문은 취소하거나 되돌릴 수 없다.
+    for status in ["cancelled", "pending", "paid", "shipped"] {
+        let (code, body) = app
+            .put("/orders", json!({ "id": id, "status": status }))
+            .await;
+        assert_eq!(code, StatusCode::CONFLICT, "{status}");
+        assert_eq!(
+            body,
+            format!("Cannot change order status from shipped to {status}")
+        );
+    }
+
+    let order = place_order(&app, &user_id, &product_ids).await;
+    let (code, order) = app
+        .put(
+            "/orders",
+            json!({ "id": order["id"], "status": "cancelled" }),
+        )
+        .await;
+    assert_eq!(code, StatusCode::OK);
+    assert_eq!(order["status"], "cancelled");
+    let (code, _) = app
+        .put("/orders", json!({ "id": order["id"], "status": "paid" }))
+        .await;
+    assert_eq!(code, StatusCode::CONFLICT);
+
+    let (code, _) = app
+        .put("/orders", json!({ "id": 9999, "status": "paid" }))
+        .await;
+    assert_eq!(code, StatusCode::NOT_FOUND);
+    let (code, _) = app
+        .put("/orders", json!({ "id": id, "status": "lost" }))
+        .await;
+    assert_eq!(code, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn status_update_fails_when_status_changed_concurrently() {
+    let app = TestApp::spawn().await;
+    let (user_id, product_ids) = create_user_and_products(&app).await;
+    let id = place_order(&app, &user_id, &product_ids).await["id"]
+        .as_i64()
+        .unwrap() as i32;
+
+    // 다른 요청이 먼저 취소한 주문을 pending으로 읽은 요청이 결제 처리하려는 경우
+    let order = update_order_status_from_database(
+        &app.pool,
+        id,
+        OrderStatus::Pending,
+        OrderStatus::Cancelled,
+    )
+    .await
+    .unwrap();
+    assert_eq!(order.status, "cancelled");
+
+    let result =
+        update_order_status_from_database(&app.pool, id, OrderStatus::Pending, OrderStatus::Paid)
+            .await;
+    assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+
+    let (_, orders) = app.get(&format!("/orders?user_id={user_id}")).await;
+    assert_eq!(orders[0]["status"], "cancelled");
+}
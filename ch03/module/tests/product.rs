@@ -0,0 +1,150 @@
+//! `/product` 핸들러 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+/// 카테고리 트리와 상품 세 개를 만들고 상품을 반환한다.
+async fn create_products(app: &TestApp) -> Vec<Value> {
+    for (name, parent) in [
+        ("Electronics", None),
+        ("Laptop", Some("Electronics")),
+        ("Clothes", None),
+    ] {
+        app.post("/category", json!({ "name": name, "parent": parent }))
+            .await;
+    }
+
+    let mut products = Vec::new();
+    for (title, price, category) in [
+        ("Radio", 100, "Electronics"),
+        ("MacBook", 2000, "Laptop"),
+        ("Jacket", 100, "Clothes"),
+    ] {
+        let (status, product) = app
+            .post(
+                "/product",
+                json!({ "title": title, "price": price, "category": category }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{title}");
+        products.push(product);
+    }
+    products
+}
+
+fn titles(products: &Value) -> Vec<&str> {
+    let mut titles = products
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|product| product["title"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    titles.sort();
+    titles
+}
+
+#[tokio::test]
+async fn post_product_requires_all_fields() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+    assert_eq!(products[0]["title"], "Radio");
+    assert_eq!(products[0]["price"], 100);
+    assert_eq!(products[0]["category"], "Electronics");
+
+    let (status, _) = app
+        .post("/product", json!({ "title": "Pen", "price": 1 }))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_product_filters_by_every_field() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+
+    let (status, all) = app.get("/product").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&all), ["Jacket", "MacBook", "Radio"]);
+
+    let (status, found) = app.get(&format!("/product?id={}", products[1]["id"])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(found, json!([products[1]]));
+
+    let (_, found) = app.get("/product?price=100").await;
+    assert_eq!(titles(&found), ["Jacket", "Radio"]);
+
+    let (_, found) = app.get("/product?title=Jacket&price=100").await;
+    assert_eq!(titles(&found), ["Jacket"]);
+
+    let (_, found) = app.get("/product?category=Electronics").await;
+    assert_eq!(titles(&found), ["Radio"]);
+
+    let (_, found) = app
+        .get("/product?category=Electronics&include_subcategories=true")
+        .await;
+    assert_eq!(titles(&found), ["MacBook", "Radio"]);
+
+    let (_, found) = app
+        .get("/product?category=Electronics&include_subcategories=true&price=2000")
+        .await;
+    assert_eq!(titles(&found), ["MacBook"]);
+
+    let (status, _) = app.get("/product?price=cheap").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn put_product_updates_given_fields() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+    let id = &products[0]["id"];
+
+    let (status, product) = app.put("/product", json!({ "id": id, "price": 150 })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        product,
+        json!({ "id": id, "title": "Radio", "price": 150, "category": "Electronics" })
+    );
+
+    let (status, product) = app
+        .put(
+            "/product",
+            json!({ "id": id, "title": "Old Radio", "category": "Clothes" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        product,
+        json!({ "id": id, "title": "Old Radio", "price": 150, "category": "Clothes" })
+    );
+
+    let (status, _) = app.put("/product", json!({ "id": 9999, "price": 1 })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.put("/product", json!({ "price": 1 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn delete_product_removes_product() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+    let id = &products[0]["id"];
+
+    let (status, message) = app.delete(&format!("/product?id={id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(message, "Product deleted");
+    let (_, products) = app.get("/product").await;
+    assert_eq!(titles(&products), ["Jacket", "MacBook"]);
+
+    let (status, _) = app.delete(&format!("/product?id={id}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.delete("/product?id=abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.delete("/product").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,157 @@
+//! `/users` 핸들러 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::json;
+
+use common::TestApp;
+
+#[tokio::test]
+async fn post_user_normalizes_username_and_rejects_duplicates() {
+    let app = TestApp::spawn().await;
+
+    let (status, user) = app
+        .post(
+            "/users",
+            json!({ "username": "  Alice ", "password": "pw" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["username"], "alice");
+    assert_eq!(user["password"], "pw");
+
+    let (status, _) = app
+        .post(
+            "/users",
+            json!({ "username": "ALICE", "password": "other" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = app.post("/users", json!({ "username": "bob" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = app
+        .post(
+            "/users",
+            json!({ "username": "two words", "password": "pw" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, message) = app
+        .post(
+            "/users",
+            json!({ "username": "al\u{7}ice", "password": "pw" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(message, "Username must not contain control characters");
+}
+
+#[tokio::test]
+async fn get_user_filters_by_id_and_username() {
+    let app = TestApp::spawn().await;
+    let (_, alice) = app
+        .post("/users", json!({ "username": "alice", "password": "pw" }))
+        .await;
+    app.post("/users", json!({ "username": "bob", "password": "pw" }))
+        .await;
+
+    // /users는 맞는 유저 하나를, /users/list는 맞는 유저 모두를 반환한다.
+    let (status, user) = app.get("/users").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user, alice);
+    let (status, users) = app.get("/users/list").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users.as_array().unwrap().len(), 2);
+
+    let (_, user) = app.get(&format!("/users?id={}", alice["id"])).await;
+    assert_eq!(user, alice);
+
+    // 이름은 대소문자를 구분하지 않고 찾는다.
+    let (_, user) = app.get("/users?username=ALICE").await;
+    assert_eq!(user, alice);
+
+    let uri = format!("id={}&username=bob", alice["id"]);
+    let (status, message) = app.get(&format!("/users?{uri}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(message, "User not found");
+    let (status, users) = app.get(&format!("/users/list?{uri}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users, json!([]));
+
+    // match=any면 조건 중 하나만 맞아도 찾는다.
+    let (_, users) = app.get(&format!("/users/list?{uri}&match=any")).await;
+    assert_eq!(users.as_array().unwrap().len(), 2);
+    let (_, user) = app.get(&format!("/users?{uri}&match=any")).await;
+    assert_eq!(user, alice);
+
+    let (status, _) = app.get("/users?id=abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.get("/users/list?match=some").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn put_user_updates_given_fields() {
+    let app = TestApp::spawn().await;
+    let (_, alice) = app
+        .post("/users", json!({ "username": "alice", "password": "pw" }))
+        .await;
+    app.post("/users", json!({ "username": "bob", "password": "pw" }))
+        .await;
+
+    let (status, user) = app
+        .put("/users", json!({ "id": alice["id"], "password": "new" }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["username"], "alice");
+    assert_eq!(user["password"], "new");
+
+    let (status, user) = app
+        .put("/users", json!({ "id": alice["id"], "username": "Carol" }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["username"], "carol");
+
+    let (status, _) = app
+        .put("/users", json!({ "id": alice["id"], "username": "BOB" }))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = app
+        .put("/users", json!({ "id": 9999, "password": "pw" }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.put("/users", json!({ "id": alice["id"] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = app.put("/users", json!({ "password": "pw" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn delete_user_removes_user() {
+    let app = TestApp::spawn().await;
+    let (_, alice) = app
+        .post("/users", json!({ "username": "alice", "password": "pw" }))
+        .await;
+
+    let (status, body) = app.delete(&format!("/users?id={}", alice["id"])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "User deleted");
+
+    let (status, _) = app.get("/users").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, users) = app.get("/users/list").await;
+    assert_eq!(users, json!([]));
+
+    let (status, _) = app.delete("/users").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = app.delete("/users?id=abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,110 @@
+//! 요청마다 연결하는 방식과 연결 풀 하나를 함께 쓰는 방식의 처리량을 비교한다.
+//!
+//! 로컬 Postgres가 떠 있어야 한다.
+//!
+//! ```sh
+//! cargo bench --bench connection
+//! BENCH_REQUESTS=2000 BENCH_CONCURRENCY=32 cargo bench --bench connection
+//! ```
+
+use std::{
+    env,
+    time::{Duration, Instant},
+};
+
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr, Statement};
+use sea_orm_example::db::DbConfig;
+
+/// `GET /users?id=1`이 보내는 것과 같은 모양의 쿼리
+const QUERY: &str = "SELECT id, username, password FROM users WHERE id = 1";
+
+fn env_or(key: &str, default: usize) -> usize {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+async fn lookup(conn: &DatabaseConnection) -> Result<(), DbErr> {
+    let backend = conn.get_database_backend();
+    conn.query_one(Statement::from_string(backend, QUERY))
+        .await?;
+    Ok(())
+}
+
+/// 요청 `requests`개를 `concurrency`개씩 동시에 보내고 걸린 시간을 잰다.
+async fn run<F, Fut>(requests: usize, concurrency: usize, request: F) -> Duration
+where
+    F: Fn() -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<(), DbErr>> + Send,
+{
+    let start = Instant::now();
+    let workers = (0..concurrency)
+        .map(|worker| {
+            let request = request.clone();
+            // 요청을 작업마다 고르게 나눈다.
+            let count = requests / concurrency + usize::from(worker < requests % concurrency);
+            tokio::spawn(async move {
+                for _ in 0..count {
+                    request().await.expect("Query failed");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker.await.unwrap();
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, requests: usize, elapsed: Duration) {
+    println!(
+        "{name:<24} {requests}건 {:>8.1}ms  {:>8.1} req/s",
+        elapsed.as_secs_f64() * 1000.0,
+        requests as f64 / elapsed.as_secs_f64()
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let config = DbConfig::from_env();
+    let requests = env_or("BENCH_REQUESTS", 500);
+    let concurrency = env_or("BENCH_CONCURRENCY", 8).max(1);
+
+    let pool = match config.connect().await {
+        Ok(pool) => pool,
+        Err(err) => {
+            println!("데이터베이스에 연결할 수 없어서 벤치마크를 건너뜁니다: {err}");
+            return;
+        }
+    };
+    println!(
+        "요청 {requests}건, 동시 요청 {concurrency}개, 최대 연결 {}개",
+        config.max_connections
+    );
+
+    // 예전 핸들러처럼 요청마다 풀을 새로 만들고 닫는다.
+    let url = config.url.clone();
+    let elapsed = run(requests, concurrency, move || {
+        let url = url.clone();
+        async move {
+            let conn = Database::connect(url).await?;
+            lookup(&conn).await?;
+            conn.close().await
+        }
+    })
+    .await;
+    report("요청마다 연결", requests, elapsed);
+
+    // run_app처럼 풀 하나를 복제해서 함께 쓴다.
+    let shared = pool.clone();
+    let elapsed = run(requests, concurrency, move || {
+        let conn = shared.clone();
+        async move { lookup(&conn).await }
+    })
+    .await;
+    report("공유 연결 풀", requests, elapsed);
+
+    pool.close().await.unwrap();
+}
@@ -1,12 +1,31 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20260410_152648_create_table;
+mod m20260420_101500_create_cart_order;
+mod m20260421_093000_add_category_parent;
+mod m20260422_140000_create_product_image;
+mod m20261019_090000_add_indexes;
+mod m20261019_093000_add_timestamps;
+mod m20261019_100000_username_case_insensitive;
+
+pub mod seed;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod username;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20260410_152648_create_table::Migration)]
+        vec![
+            Box::new(m20260410_152648_create_table::Migration),
+            Box::new(m20260420_101500_create_cart_order::Migration),
+            Box::new(m20260421_093000_add_category_parent::Migration),
+            Box::new(m20260422_140000_create_product_image::Migration),
+            Box::new(m20261019_090000_add_indexes::Migration),
+            Box::new(m20261019_093000_add_timestamps::Migration),
+            Box::new(m20261019_100000_username_case_insensitive::Migration),
+        ]
     }
 }
@@ -1,4 +1,4 @@
-use sea_orm_migration::{prelude::*, schema::*};
+use sea_orm_migration::prelude::*;
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -89,7 +89,14 @@ impl MigrationTrait for Migration {
             .await
     }
 
+    /// product가 category를 참조하므로 product부터 지운다.
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Product::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Category::Table).if_exists().to_owned())
+            .await?;
         manager
             .drop_table(Table::drop().table(Users::Table).if_exists().to_owned())
             .await
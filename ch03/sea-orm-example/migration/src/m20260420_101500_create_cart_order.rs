@@ -0,0 +1,146 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Cart {
+    Table,
+    UserId,
+    ProductId,
+    Quantity,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+    UserId,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum OrderLine {
+    Table,
+    Id,
+    OrderId,
+    ProductId,
+    Title,
+    Price,
+    Quantity,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Cart::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Cart::UserId).integer().not_null())
+                    .col(ColumnDef::new(Cart::ProductId).integer().not_null())
+                    .col(ColumnDef::new(Cart::Quantity).integer().not_null())
+                    .primary_key(Index::create().col(Cart::UserId).col(Cart::ProductId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_cart_user")
+                            .from(Cart::Table, Cart::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_cart_product")
+                            .from(Cart::Table, Cart::ProductId)
+                            .to(Product::Table, Product::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Orders::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Orders::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Orders::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(Orders::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_orders_user")
+                            .from(Orders::Table, Orders::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 주문 당시의 상품 정보를 보존하기 위해 product를 참조하지 않고 값을 복사해 둔다.
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OrderLine::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrderLine::OrderId).integer().not_null())
+                    .col(ColumnDef::new(OrderLine::ProductId).integer().not_null())
+                    .col(ColumnDef::new(OrderLine::Title).string().not_null())
+                    .col(ColumnDef::new(OrderLine::Price).integer().not_null())
+                    .col(ColumnDef::new(OrderLine::Quantity).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_order_line_orders")
+                            .from(OrderLine::Table, OrderLine::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrderLine::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Orders::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Cart::Table).if_exists().to_owned())
+            .await
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Name,
+    Parent,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Category::Table)
+                    .add_column(ColumnDef::new(Category::Parent).string().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_category_parent")
+                            .from_tbl(Category::Table)
+                            .from_col(Category::Parent)
+                            .to_tbl(Category::Table)
+                            .to_col(Category::Name),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Category::Table)
+                    .drop_foreign_key(Alias::new("fk_category_parent"))
+                    .drop_column(Category::Parent)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ProductImage {
+    Table,
+    Id,
+    ProductId,
+    FileName,
+    ContentType,
+    Size,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductImage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProductImage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ProductImage::ProductId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ProductImage::FileName)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductImage::ContentType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ProductImage::Size).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_product_image_product")
+                            .from(ProductImage::Table, ProductImage::ProductId)
+                            .to(Product::Table, Product::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ProductImage::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
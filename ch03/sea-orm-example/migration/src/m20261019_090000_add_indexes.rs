@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+use crate::username::ensure_unique;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Username,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Title,
+    Category,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 처음 스키마는 같은 이름을 막지 않았으므로 겹치는 이름이 있으면 먼저 알려준다.
+        ensure_unique(manager, "username", "idx_users_username").await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_username")
+                    .table(Users::Table)
+                    .col(Users::Username)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 카테고리별 상품 조회와 외래 키 검사에 쓴다.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_product_category")
+                    .table(Product::Table)
+                    .col(Product::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_product_title")
+                    .table(Product::Table)
+                    .col(Product::Title)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_product_title")
+                    .table(Product::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_product_category")
+                    .table(Product::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_users_username")
+                    .table(Users::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
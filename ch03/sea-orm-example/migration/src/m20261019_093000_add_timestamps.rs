@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Timestamps {
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// 행을 바꿀 때마다 `updated_at`을 현재 시각으로 바꾸는 트리거 함수
+const SET_UPDATED_AT: &str = r#"
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at = CURRENT_TIMESTAMP;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql
+"#;
+
+/// 타임스탬프를 붙일 테이블
+fn tables() -> [DynIden; 3] {
+    [
+        Users::Table.into_iden(),
+        Category::Table.into_iden(),
+        Product::Table.into_iden(),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(SET_UPDATED_AT).await?;
+
+        // 이미 있는 행은 마이그레이션한 시각으로 채운다.
+        for table in tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table.clone())
+                        .add_column(
+                            ColumnDef::new(Timestamps::CreatedAt)
+                                .timestamp_with_time_zone()
+                                .not_null()
+                                .default(Expr::current_timestamp()),
+                        )
+                        .add_column(
+                            ColumnDef::new(Timestamps::UpdatedAt)
+                                .timestamp_with_time_zone()
+                                .not_null()
+                                .default(Expr::current_timestamp()),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+
+            let table = table.to_string();
+            db.execute_unprepared(&format!(
+                "CREATE TRIGGER {table}_set_updated_at BEFORE UPDATE ON {table} \
+                FOR EACH ROW EXECUTE FUNCTION set_updated_at()"
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        for table in tables() {
+            db.execute_unprepared(&format!(
+                "DROP TRIGGER IF EXISTS {name}_set_updated_at ON {name}",
+                name = table.to_string()
+            ))
+            .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Timestamps::CreatedAt)
+                        .drop_column(Timestamps::UpdatedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        db.execute_unprepared("DROP FUNCTION IF EXISTS set_updated_at()")
+            .await?;
+        Ok(())
+    }
+}
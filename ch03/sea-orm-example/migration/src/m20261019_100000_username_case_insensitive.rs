@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+use crate::username::ensure_unique;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Username,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// 대소문자만 다른 유저 이름을 막도록 `lower(username)`에 유니크 인덱스를 건다.
+    /// `lower(username) = $1`로 찾을 때도 이 인덱스를 쓴다.
+    /// API가 저장하는 형태와 같도록 기존 이름도 앞뒤 공백을 지우고 소문자로 바꾼다.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 바꾼 뒤에 겹칠 이름이 있으면 아무것도 바꾸지 않고 고칠 행을 알려준다.
+        ensure_unique(
+            manager,
+            "lower(btrim(username))",
+            "idx_users_username_lower",
+        )
+        .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_users_username")
+                    .table(Users::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users SET username = lower(btrim(username)) \
+                 WHERE username <> lower(btrim(username))",
+            )
+            .await?;
+
+        // sea-query의 인덱스 정의로는 식 인덱스를 만들 수 없어서 SQL을 직접 쓴다.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE UNIQUE INDEX idx_users_username_lower ON users (lower(username))",
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 소문자로 바꾼 이름은 원래대로 되돌리지 않는다.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_users_username_lower")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_username")
+                    .table(Users::Table)
+                    .col(Users::Username)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -1,6 +1,25 @@
+use clap::Parser;
+use migration::seed::{self, SeedArgs};
 use sea_orm_migration::prelude::*;
 
+/// `seed` 명령은 sea-orm-migration CLI에 없으므로 따로 읽는다.
+#[derive(Parser)]
+#[command(name = "migration seed", about = "Load fixtures into the database")]
+struct SeedCli {
+    #[command(flatten)]
+    args: SeedArgs,
+}
+
 #[async_std::main]
 async fn main() {
+    if std::env::args().nth(1).as_deref() == Some("seed") {
+        let cli = SeedCli::parse_from(std::env::args().skip(1));
+        if let Err(err) = seed::run_with_env(&cli.args).await {
+            eprintln!("시드 데이터를 넣지 못했습니다: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     cli::run_cli(migration::Migrator).await;
 }
@@ -0,0 +1,294 @@
+//! 개발과 테스트에 쓸 데이터를 넣는 `seed` 명령
+//!
+//! ```sh
+//! cargo run -- seed                          # fixtures/dev.yaml을 넣는다.
+//! cargo run -- seed fixtures/a.json b.yaml   # 여러 파일을 차례로 넣는다.
+//! cargo run -- seed --reset                  # 모든 데이터를 지우고 fixtures/dev.yaml을 넣는다.
+//! cargo run -- seed --random 10000           # 임의의 상품 10000개를 더 넣는다.
+//! ```
+//!
+//! 같은 파일을 여러 번 넣어도 결과가 같다.
+//! 유저는 이름, 카테고리는 이름, 상품은 제목과 카테고리로 같은 행을 찾아서 값을 덮어쓴다.
+
+use std::path::{Path, PathBuf};
+
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement, TransactionTrait},
+};
+use serde::Deserialize;
+
+/// `seed` 명령의 인자
+#[derive(clap::Args, Debug)]
+pub struct SeedArgs {
+    /// 넣을 fixture 파일. 확장자가 .json이면 JSON, .yaml이나 .yml이면 YAML로 읽는다.
+    #[arg(default_value = "fixtures/dev.yaml")]
+    pub files: Vec<PathBuf>,
+    /// fixture를 넣기 전에 모든 테이블의 데이터를 지운다. 스키마는 그대로 둔다.
+    #[arg(long)]
+    pub reset: bool,
+    /// fixture를 넣은 뒤 이미 있는 카테고리에 임의의 상품을 이만큼 더 넣는다.
+    #[arg(long, value_name = "N")]
+    pub random: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fixtures {
+    pub users: Vec<UserFixture>,
+    /// 부모 카테고리가 뒤에 나와도 된다.
+    pub categories: Vec<CategoryFixture>,
+    pub products: Vec<ProductFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserFixture {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryFixture {
+    pub name: String,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProductFixture {
+    pub title: String,
+    pub price: i32,
+    pub category: String,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Username,
+    Password,
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Name,
+    Parent,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Title,
+    Price,
+    Category,
+}
+
+impl Fixtures {
+    /// 확장자를 보고 JSON이나 YAML 파일을 읽는다.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&text).map_err(|err| err.to_string()),
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|err| err.to_string()),
+            _ => Err("Fixture file must end with .json, .yaml or .yml".to_string()),
+        }
+        .map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+/// `seaql_migrations`를 뺀 모든 테이블의 데이터를 지우고 id 시퀀스를 처음으로 돌린다.
+/// 마이그레이션으로 테이블이 늘어도 따로 고칠 필요가 없도록 테이블 목록은 데이터베이스에서 읽는다.
+pub async fn reset(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    let tables = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT quote_ident(table_name) AS name FROM information_schema.tables \
+             WHERE table_schema = current_schema() AND table_type = 'BASE TABLE' \
+             AND table_name <> 'seaql_migrations'",
+        ))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    db.execute_unprepared(&format!(
+        "TRUNCATE {} RESTART IDENTITY CASCADE",
+        tables.join(", ")
+    ))
+    .await?;
+    Ok(())
+}
+
+/// fixture를 넣는다. 이미 있는 행은 fixture의 값으로 덮어쓴다.
+pub async fn load(db: &impl ConnectionTrait, fixtures: &Fixtures) -> Result<(), DbErr> {
+    let builder = db.get_database_backend();
+
+    for user in &fixtures.users {
+        // API와 같은 규칙으로 이름을 바꿔서 `lower(username)` 유니크 인덱스와 맞춘다.
+        let username = crate::username::normalize(&user.username)
+            .map_err(|err| DbErr::Custom(format!("User {:?}: {err}", user.username)))?;
+        let insert = Query::insert()
+            .into_table(Users::Table)
+            .columns([Users::Username, Users::Password])
+            .values_panic([username.into(), user.password.clone().into()])
+            .on_conflict(
+                OnConflict::new()
+                    .expr(Func::lower(Expr::col(Users::Username)))
+                    .update_column(Users::Password)
+                    .to_owned(),
+            )
+            .to_owned();
+        db.execute(builder.build(&insert)).await?;
+    }
+
+    // 부모가 아직 없을 수 있으므로 카테고리를 모두 만든 뒤에 부모를 연결한다.
+    for category in &fixtures.categories {
+        let insert = Query::insert()
+            .into_table(Category::Table)
+            .columns([Category::Name])
+            .values_panic([category.name.clone().into()])
+            .on_conflict(OnConflict::column(Category::Name).do_nothing().to_owned())
+            .to_owned();
+        db.execute(builder.build(&insert)).await?;
+    }
+    for category in &fixtures.categories {
+        let update = Query::update()
+            .table(Category::Table)
+            .value(Category::Parent, category.parent.clone())
+            .and_where(Expr::col(Category::Name).eq(category.name.clone()))
+            .to_owned();
+        db.execute(builder.build(&update)).await?;
+    }
+
+    // 상품에는 유니크 키가 없으므로 제목과 카테고리가 같은 상품을 찾아서 바꾼다.
+    for product in &fixtures.products {
+        let update = Query::update()
+            .table(Product::Table)
+            .value(Product::Price, product.price)
+            .and_where(Expr::col(Product::Title).eq(product.title.clone()))
+            .and_where(Expr::col(Product::Category).eq(product.category.clone()))
+            .to_owned();
+        if db.execute(builder.build(&update)).await?.rows_affected() > 0 {
+            continue;
+        }
+
+        let insert = Query::insert()
+            .into_table(Product::Table)
+            .columns([Product::Title, Product::Price, Product::Category])
+            .values_panic([
+                product.title.clone().into(),
+                product.price.into(),
+                product.category.clone().into(),
+            ])
+            .to_owned();
+        db.execute(builder.build(&insert)).await?;
+    }
+
+    Ok(())
+}
+
+const ADJECTIVES: [&str; 8] = [
+    "Red", "Small", "Classic", "Smart", "Heavy", "Quiet", "Rapid", "Vintage",
+];
+const NOUNS: [&str; 8] = [
+    "Chair", "Lamp", "Keyboard", "Backpack", "Mug", "Speaker", "Jacket", "Clock",
+];
+
+/// 이미 있는 카테고리에 임의의 상품 `count`개를 넣는다.
+pub async fn random_products(db: &impl ConnectionTrait, count: usize) -> Result<(), DbErr> {
+    let builder = db.get_database_backend();
+    let select = Query::select()
+        .column(Category::Name)
+        .from(Category::Table)
+        .to_owned();
+    let categories = db
+        .query_all(builder.build(&select))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if categories.is_empty() {
+        return Err(DbErr::Custom(
+            "Random products need at least one category".to_string(),
+        ));
+    }
+
+    // 한 문장에 너무 많은 값을 넣지 않도록 나눠서 넣는다.
+    let mut remaining = count;
+    while remaining > 0 {
+        let chunk = remaining.min(1000);
+        remaining -= chunk;
+
+        let mut insert = Query::insert()
+            .into_table(Product::Table)
+            .columns([Product::Title, Product::Price, Product::Category])
+            .to_owned();
+        for _ in 0..chunk {
+            let title = format!(
+                "{} {} {}",
+                ADJECTIVES[fastrand::usize(..ADJECTIVES.len())],
+                NOUNS[fastrand::usize(..NOUNS.len())],
+                fastrand::u32(1000..10000)
+            );
+            let price = fastrand::i32(1..1000) * 100;
+            let category = categories[fastrand::usize(..categories.len())].clone();
+            insert.values_panic([title.into(), price.into(), category.into()]);
+        }
+        db.execute(builder.build(&insert)).await?;
+    }
+
+    Ok(())
+}
+
+/// `seed` 명령을 실행한다. 모든 작업을 트랜잭션 하나로 묶어서 실패하면 아무것도 바꾸지 않는다.
+pub async fn run(db: &DatabaseConnection, args: &SeedArgs) -> Result<(), String> {
+    let fixtures = args
+        .files
+        .iter()
+        .map(|path| Fixtures::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let txn = db.begin().await.map_err(|err| err.to_string())?;
+    if args.reset {
+        reset(&txn).await.map_err(|err| err.to_string())?;
+        println!("모든 데이터를 지웠습니다");
+    }
+    for (path, fixtures) in args.files.iter().zip(&fixtures) {
+        load(&txn, fixtures)
+            .await
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        println!(
+            "{}: 유저 {}명, 카테고리 {}개, 상품 {}개",
+            path.display(),
+            fixtures.users.len(),
+            fixtures.categories.len(),
+            fixtures.products.len()
+        );
+    }
+    if let Some(count) = args.random {
+        random_products(&txn, count)
+            .await
+            .map_err(|err| err.to_string())?;
+        println!("임의의 상품 {count}개를 넣었습니다");
+    }
+    txn.commit().await.map_err(|err| err.to_string())
+}
+
+/// DATABASE_URL에 연결해서 `seed` 명령을 실행한다.
+pub async fn run_with_env(args: &SeedArgs) -> Result<(), String> {
+    dotenvy::dotenv().ok();
+    let url = std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL is not set".to_string())?;
+    let db = Database::connect(&url)
+        .await
+        .map_err(|err| err.to_string())?;
+    let result = run(&db, args).await;
+    db.close().await.map_err(|err| err.to_string())?;
+    result
+}
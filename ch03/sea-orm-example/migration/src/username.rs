@@ -0,0 +1,41 @@
+//! 유저 이름 규칙을 데이터베이스에 적용하는 마이그레이션 도우미
+//!
+//! 규칙 자체는 `username` 크레이트에 있다. 여기서는 기존 데이터가 규칙을 어기는지 확인한다.
+
+use sea_orm_migration::{prelude::*, sea_orm::Statement};
+
+pub use ::username::{normalize, UsernameError, MAX_LEN};
+
+/// 한 번에 보여줄 겹치는 이름 수
+const MAX_REPORTED: i64 = 20;
+
+/// `key` 식의 값이 같은 유저가 있으면 그 유저들을 모두 적은 오류를 반환한다.
+/// 유니크 인덱스를 만들기 전에 불러서 Postgres 오류 대신 고칠 행을 알려준다.
+pub(crate) async fn ensure_unique(
+    manager: &SchemaManager<'_>,
+    key: &str,
+    index: &str,
+) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let sql = format!(
+        "SELECT {key} AS key, string_agg(id || ':' || username, ', ' ORDER BY id) AS users \
+         FROM users GROUP BY {key} HAVING count(*) > 1 ORDER BY key LIMIT {MAX_REPORTED}"
+    );
+    let rows = db
+        .query_all(Statement::from_string(db.get_database_backend(), sql))
+        .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "Cannot create unique index {index}: these users share the same {key} (id:username). \
+         Rename or delete all but one of each group, then run the migration again."
+    );
+    for row in rows {
+        let key: String = row.try_get("", "key")?;
+        let users: String = row.try_get("", "users")?;
+        message.push_str(&format!("\n  {key}: {users}"));
+    }
+    Err(DbErr::Migration(message))
+}
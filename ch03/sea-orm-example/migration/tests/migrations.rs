@@ -0,0 +1,134 @@
+//! 모든 마이그레이션을 올리고, 내리고, 다시 올리는 테스트
+//!
+//! 로컬 Postgres(DATABASE_URL)에 테스트마다 새 데이터베이스를 만들어서 실행하고 끝나면 지운다.
+
+use migration::{testing::TestDatabase, Migrator, MigratorTrait};
+use sea_orm_migration::{
+    sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement},
+    SchemaManager,
+};
+
+const TABLES: [&str; 7] = [
+    "users",
+    "category",
+    "product",
+    "cart",
+    "orders",
+    "order_line",
+    "product_image",
+];
+
+async fn assert_tables(db: &DatabaseConnection, exist: bool) {
+    let manager = SchemaManager::new(db);
+    for table in TABLES {
+        assert_eq!(manager.has_table(table).await.unwrap(), exist, "{table}");
+    }
+}
+
+#[async_std::test]
+async fn migrations_go_up_down_and_up_again() {
+    let database = TestDatabase::create("migration_test").await;
+    let db = Database::connect(database.url()).await.unwrap();
+
+    Migrator::up(&db, None).await.unwrap();
+    assert_tables(&db, true).await;
+
+    // 모든 마이그레이션을 내리면 테이블이 하나도 남지 않아야 다시 올릴 수 있다.
+    Migrator::down(&db, None).await.unwrap();
+    assert_tables(&db, false).await;
+
+    Migrator::up(&db, None).await.unwrap();
+    assert_tables(&db, true).await;
+    let manager = SchemaManager::new(&db);
+    assert!(manager
+        .has_index("users", "idx_users_username_lower")
+        .await
+        .unwrap());
+    assert!(manager.has_column("product", "updated_at").await.unwrap());
+
+    db.close().await.unwrap();
+}
+
+#[async_std::test]
+async fn duplicate_usernames_are_reported_before_creating_the_index() {
+    let database = TestDatabase::create("migration_dup_test").await;
+    let db = Database::connect(database.url()).await.unwrap();
+
+    // 인덱스를 만들기 전까지만 올리고 같은 이름을 넣는다.
+    Migrator::up(&db, Some(4)).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO users (username, password) VALUES ('bob', 'a'), ('bob', 'b'), ('alice', 'c')",
+    )
+    .await
+    .unwrap();
+
+    let err = Migrator::up(&db, None).await.unwrap_err().to_string();
+    assert!(err.contains("idx_users_username"), "{err}");
+    assert!(err.contains("bob: 1:bob, 2:bob"), "{err}");
+    assert!(!err.contains("alice"), "{err}");
+
+    db.execute_unprepared("DELETE FROM users WHERE id = 2")
+        .await
+        .unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    db.close().await.unwrap();
+}
+
+#[async_std::test]
+async fn case_variant_usernames_are_reported_then_lowercased() {
+    let database = TestDatabase::create("migration_case_test").await;
+    let db = Database::connect(database.url()).await.unwrap();
+
+    // 대소문자를 구분하지 않는 인덱스를 만들기 전까지만 올린다.
+    Migrator::up(&db, Some(6)).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO users (username, password) VALUES ('Bob', 'a'), ('bob', 'b'), (' Carol ', 'c')",
+    )
+    .await
+    .unwrap();
+
+    let err = Migrator::up(&db, None).await.unwrap_err().to_string();
+    assert!(err.contains("idx_users_username_lower"), "{err}");
+    assert!(err.contains("bob: 1:Bob, 2:bob"), "{err}");
+
+    db.execute_unprepared("DELETE FROM users WHERE id = 2")
+        .await
+        .unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT username FROM users ORDER BY id",
+        ))
+        .await
+        .unwrap();
+    let usernames = rows
+        .iter()
+        .map(|row| row.try_get::<String>("", "username").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(usernames, ["bob", "carol"]);
+
+    db.close().await.unwrap();
+}
+
+#[async_std::test]
+async fn test_database_is_dropped_with_its_guard() {
+    let database = TestDatabase::create("migration_drop_test").await;
+    let name = database.name().to_string();
+    let admin_url = database.url().replace(&name, "postgres");
+    drop(database);
+
+    let admin = Database::connect(&admin_url).await.unwrap();
+    let row = admin
+        .query_one(Statement::from_string(
+            admin.get_database_backend(),
+            format!("SELECT count(*) AS count FROM pg_database WHERE datname = '{name}'"),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.try_get::<i64>("", "count").unwrap(), 0);
+    admin.close().await.unwrap();
+}
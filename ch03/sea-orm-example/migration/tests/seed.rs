@@ -0,0 +1,53 @@
+//! fixture를 여러 번 넣어도 결과가 같은지 확인하는 테스트
+//!
+//! 로컬 Postgres(DATABASE_URL)에 테스트마다 새 데이터베이스를 만들어서 실행하고 끝나면 지운다.
+
+use migration::{
+    seed::{self, Fixtures},
+    testing::TestDatabase,
+};
+use sea_orm_migration::sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement};
+
+async fn count(db: &DatabaseConnection, table: &str) -> i64 {
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            format!("SELECT count(*) AS count FROM {table}"),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    row.try_get("", "count").unwrap()
+}
+
+#[async_std::test]
+async fn seed_is_idempotent_and_reset_clears_data() {
+    let database = TestDatabase::migrated("seed_test").await;
+    let db = Database::connect(database.url()).await.unwrap();
+
+    let fixtures =
+        Fixtures::load(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dev.yaml").as_ref()).unwrap();
+    seed::load(&db, &fixtures).await.unwrap();
+    seed::load(&db, &fixtures).await.unwrap();
+    assert_eq!(count(&db, "users").await, fixtures.users.len() as i64);
+    assert_eq!(
+        count(&db, "category").await,
+        fixtures.categories.len() as i64
+    );
+    assert_eq!(count(&db, "product").await, fixtures.products.len() as i64);
+
+    seed::random_products(&db, 1500).await.unwrap();
+    assert_eq!(
+        count(&db, "product").await,
+        fixtures.products.len() as i64 + 1500
+    );
+
+    seed::reset(&db).await.unwrap();
+    assert_eq!(count(&db, "users").await, 0);
+    assert_eq!(count(&db, "product").await, 0);
+    // 마이그레이션 기록은 지우지 않는다.
+    assert!(count(&db, "seaql_migrations").await > 0);
+    assert!(seed::random_products(&db, 1).await.is_err());
+
+    db.close().await.unwrap();
+}
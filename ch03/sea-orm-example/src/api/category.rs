@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{Set, Unchanged},
+    ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, SqlErr,
+    TransactionTrait,
+    sea_query::{Expr, extension::postgres::PgExpr},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::users::AppError,
+    entities::{
+        category::{ActiveModel, Column, Model},
+        prelude::{Category, Product},
+        product,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct UpsertModel {
+    name: String,
+    parent: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CategoryQuery {
+    /// `products`면 카테고리에 속한 상품을 함께 가져온다.
+    include: Option<String>,
+}
+
+/// 속한 상품을 함께 담은 카테고리
+#[derive(Serialize)]
+pub struct CategoryWithProducts {
+    #[serde(flatten)]
+    category: Model,
+    products: Vec<product::Model>,
+}
+
+/// 하위 카테고리를 포함한 카테고리 트리
+#[derive(Serialize)]
+pub struct CategoryTree {
+    name: String,
+    children: Vec<CategoryTree>,
+}
+
+impl CategoryTree {
+    /// `name`을 루트로 하는 트리를 만든다.
+    /// `children`은 부모 이름을 키로 하는 하위 카테고리 이름 목록이다.
+    fn build(name: String, children: &mut HashMap<String, Vec<String>>) -> Self {
+        let child_names = children.remove(&name).unwrap_or_default();
+        let children = child_names
+            .into_iter()
+            .map(|child| CategoryTree::build(child, children))
+            .collect();
+
+        CategoryTree { name, children }
+    }
+}
+
+fn is_foreign_key_violation(err: &DbErr) -> bool {
+    matches!(
+        err.sql_err(),
+        Some(SqlErr::ForeignKeyConstraintViolation(_))
+    )
+}
+
+/// 부모 이름을 키로 하는 하위 카테고리 이름 목록을 만든다.
+/// 카테고리 수가 많지 않으므로 재귀 쿼리 대신 모두 읽어서 메모리에서 따라간다.
+async fn children_by_parent(
+    conn: &impl ConnectionTrait,
+) -> Result<HashMap<String, Vec<String>>, DbErr> {
+    let mut children = HashMap::<String, Vec<String>>::new();
+    for category in Category::find().all(conn).await? {
+        if let Some(parent) = category.parent {
+            children.entry(parent).or_default().push(category.name);
+        }
+    }
+    children.values_mut().for_each(|names| names.sort());
+    Ok(children)
+}
+
+/// 카테고리와 그 아래의 모든 하위 카테고리 이름을 가져온다.
+/// 카테고리가 없어도 `name` 하나만 담아서 반환한다.
+pub async fn subtree_names(conn: &impl ConnectionTrait, name: &str) -> Result<Vec<String>, DbErr> {
+    let mut children = children_by_parent(conn).await?;
+    let mut names = vec![name.to_string()];
+    let mut i = 0;
+    while let Some(name) = names.get(i) {
+        if let Some(child_names) = children.remove(name) {
+            names.extend(child_names);
+        }
+        i += 1;
+    }
+    Ok(names)
+}
+
+/// GET category 핸들러
+/// 쿼리를 받고 카테고리 목록을 반환한다.
+pub async fn get_category(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<Model>>, AppError> {
+    let mut select = Category::find();
+    if let Some(name) = params.get("name") {
+        select = select.filter(Expr::col(Column::Name).ilike(name));
+    }
+
+    select
+        .all(&conn)
+        .await
+        .map(Json)
+        .map_err(AppError::database)
+}
+
+/// GET category/{name} 핸들러
+/// 카테고리 하나를 반환한다. `include=products`를 주면 속한 상품을 함께 반환한다.
+pub async fn get_category_by_name(
+    State(conn): State<DatabaseConnection>,
+    Path(name): Path<String>,
+    Query(query): Query<CategoryQuery>,
+) -> Result<Response, AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Category not found");
+
+    match query.include.as_deref() {
+        None => Category::find_by_id(name)
+            .one(&conn)
+            .await
+            .map_err(AppError::database)?
+            .map(|category| Json(category).into_response())
+            .ok_or_else(not_found),
+        // 카테고리와 상품을 JOIN 한 번으로 함께 가져온다.
+        Some("products") => Category::find_by_id(name)
+            .find_with_related(Product)
+            .order_by_asc(product::Column::Id)
+            .all(&conn)
+            .await
+            .map_err(AppError::database)?
+            .into_iter()
+            .next()
+            .map(|(category, products)| {
+                Json(CategoryWithProducts { category, products }).into_response()
+            })
+            .ok_or_else(not_found),
+        Some(include) => Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown include: {include}"),
+        )),
+    }
+}
+
+/// POST category 핸들러
+/// 카테고리를 생성한다.
+pub async fn post_category(
+    State(conn): State<DatabaseConnection>,
+    Json(category): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    ActiveModel {
+        name: Set(category.name),
+        parent: Set(category.parent),
+    }
+    .insert(&conn)
+    .await
+    .map(Json)
+    .map_err(|err| {
+        if is_foreign_key_violation(&err) {
+            AppError::new(StatusCode::NOT_FOUND, "Parent category not found")
+        } else {
+            AppError::database(err)
+        }
+    })
+}
+
+/// 카테고리를 `parent` 아래로 옮긴다.
+/// 새 부모가 자기 자신이거나 하위 카테고리라서 순환이 생기면 `Ok(None)`을 반환한다.
+async fn move_category(
+    conn: &DatabaseConnection,
+    name: String,
+    parent: Option<String>,
+) -> Result<Option<Model>, DbErr> {
+    let txn = conn.begin().await?;
+
+    // 동시에 실행되는 이동이 서로의 검사를 통과해 순환을 만들지 않도록 막는다.
+    txn.execute_unprepared("LOCK TABLE category IN SHARE ROW EXCLUSIVE MODE")
+        .await?;
+
+    if let Some(parent) = &parent
+        && subtree_names(&txn, &name).await?.contains(parent)
+    {
+        return Ok(None);
+    }
+
+    let category = ActiveModel {
+        name: Unchanged(name),
+        parent: Set(parent),
+    }
+    .update(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Some(category))
+}
+
+/// PUT category 핸들러
+/// 카테고리를 `parent` 아래로 옮긴다. 하위 카테고리도 함께 옮겨진다.
+pub async fn put_category(
+    State(conn): State<DatabaseConnection>,
+    Json(category): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    match move_category(&conn, category.name, category.parent).await {
+        Ok(Some(category)) => Ok(Json(category)),
+        Ok(None) => Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Category cannot be moved under itself or its subcategory",
+        )),
+        Err(DbErr::RecordNotUpdated) => {
+            Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"))
+        }
+        Err(err) if is_foreign_key_violation(&err) => Err(AppError::new(
+            StatusCode::NOT_FOUND,
+            "Parent category not found",
+        )),
+        Err(err) => Err(AppError::database(err)),
+    }
+}
+
+/// GET category tree 핸들러
+/// 카테고리와 모든 하위 카테고리를 트리 형태로 반환한다.
+pub async fn get_category_tree(
+    State(conn): State<DatabaseConnection>,
+    Path(name): Path<String>,
+) -> Result<Json<CategoryTree>, AppError> {
+    let exists = Category::find_by_id(&name)
+        .one(&conn)
+        .await
+        .map_err(AppError::database)?
+        .is_some();
+    if !exists {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"));
+    }
+
+    let mut children = children_by_parent(&conn)
+        .await
+        .map_err(AppError::database)?;
+
+    Ok(Json(CategoryTree::build(name, &mut children)))
+}
+
+/// DELETE category 핸들러
+/// 카테고리를 삭제한다.
+pub async fn delete_category(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<&'static str>, AppError> {
+    let Some(name) = params.get("name") else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "Category name not provided",
+        ));
+    };
+
+    match Category::delete_by_id(name).exec(&conn).await {
+        Ok(result) if result.rows_affected == 0 => {
+            Err(AppError::new(StatusCode::NOT_FOUND, "Category not found"))
+        }
+        Ok(_) => Ok(Json("Deleted")),
+        Err(err) if is_foreign_key_violation(&err) => Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Category still has products or subcategories",
+        )),
+        Err(err) => Err(AppError::database(err)),
+    }
+}
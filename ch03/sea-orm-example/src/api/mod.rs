@@ -0,0 +1,3 @@
+pub mod category;
+pub mod product;
+pub mod users;
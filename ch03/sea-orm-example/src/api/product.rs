@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{category::subtree_names, users::AppError},
+    entities::{
+        category,
+        prelude::{Category, Product},
+        product::{ActiveModel, Column, Model},
+    },
+};
+
+#[derive(Deserialize)]
+pub struct UpsertModel {
+    id: Option<i32>,
+    title: Option<String>,
+    price: Option<i32>,
+    category: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ProductQuery {
+    id: Option<i32>,
+    title: Option<String>,
+    price: Option<i32>,
+    category: Option<String>,
+    /// true면 하위 카테고리에 속한 상품도 함께 가져온다.
+    include_subcategories: Option<bool>,
+    /// `category`면 상품마다 카테고리 정보를 함께 가져온다.
+    include: Option<String>,
+}
+
+/// 카테고리 정보를 함께 담은 상품
+#[derive(Serialize)]
+pub struct ProductWithCategory {
+    id: i32,
+    title: String,
+    price: i32,
+    category: category::Model,
+}
+
+/// `include=category`를 주면 상품의 `category` 필드에 이름 대신 카테고리 객체를 넣는다.
+pub async fn get_product(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<ProductQuery>,
+) -> Result<Response, AppError> {
+    let include_category = match params.include.as_deref() {
+        None => false,
+        Some("category") => true,
+        Some(include) => {
+            return Err(AppError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown include: {include}"),
+            ));
+        }
+    };
+
+    // 값이 None인 조건은 추가되지 않는다.
+    let mut condition = Condition::all()
+        .add_option(params.id.map(|id| Column::Id.eq(id)))
+        .add_option(params.title.map(|title| Column::Title.eq(title)))
+        .add_option(params.price.map(|price| Column::Price.eq(price)));
+
+    if let Some(category) = params.category {
+        condition = if params.include_subcategories.unwrap_or(false) {
+            let categories = subtree_names(&conn, &category)
+                .await
+                .map_err(AppError::database)?;
+            condition.add(Column::Category.is_in(categories))
+        } else {
+            condition.add(Column::Category.eq(category))
+        };
+    }
+
+    let select = Product::find().filter(condition).order_by_asc(Column::Id);
+    if !include_category {
+        let products = select.all(&conn).await.map_err(AppError::database)?;
+        return Ok(Json(products).into_response());
+    }
+
+    // 상품마다 카테고리를 따로 가져오지 않고 JOIN 한 번으로 함께 가져온다.
+    let products = select
+        .find_also_related(Category)
+        .all(&conn)
+        .await
+        .map_err(AppError::database)?
+        .into_iter()
+        .filter_map(|(product, category)| {
+            // 외래 키가 있으므로 카테고리가 없는 상품은 없다.
+            Some(ProductWithCategory {
+                id: product.id,
+                title: product.title,
+                price: product.price,
+                category: category?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(products).into_response())
+}
+
+pub async fn post_product(
+    State(conn): State<DatabaseConnection>,
+    Json(product): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    let (Some(title), Some(price), Some(category)) =
+        (product.title, product.price, product.category)
+    else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "title or price or category field is not set",
+        ));
+    };
+
+    ActiveModel {
+        title: Set(title),
+        price: Set(price),
+        category: Set(category),
+        ..Default::default()
+    }
+    .insert(&conn)
+    .await
+    .map(Json)
+    .map_err(AppError::database)
+}
+
+pub async fn put_product(
+    State(conn): State<DatabaseConnection>,
+    Json(product): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    let Some(id) = product.id else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "id field is not set",
+        ));
+    };
+
+    // 보낸 필드만 Set으로 두면 그 필드만 UPDATE 문에 들어간다.
+    let active = ActiveModel {
+        id: Unchanged(id),
+        title: product.title.map_or(NotSet, Set),
+        price: product.price.map_or(NotSet, Set),
+        category: product.category.map_or(NotSet, Set),
+    };
+
+    // 바꿀 필드가 없으면 지금 값을 그대로 반환한다.
+    let result = if active.is_changed() {
+        active.update(&conn).await
+    } else {
+        Product::find_by_id(id)
+            .one(&conn)
+            .await
+            .and_then(|product| {
+                product.ok_or_else(|| DbErr::RecordNotFound("Product not found".into()))
+            })
+    };
+
+    match result {
+        Ok(product) => Ok(Json(product)),
+        Err(DbErr::RecordNotUpdated | DbErr::RecordNotFound(_)) => {
+            Err(AppError::new(StatusCode::NOT_FOUND, "Product not found"))
+        }
+        Err(err) => Err(AppError::database(err)),
+    }
+}
+
+pub async fn delete_product(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<&'static str>, AppError> {
+    let Some(id) = params.get("id") else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "id field is not set",
+        ));
+    };
+    let id = id
+        .parse::<i32>()
+        .map_err(|_| AppError::new(StatusCode::BAD_REQUEST, "id must be an integer"))?;
+
+    let result = Product::delete_by_id(id)
+        .exec(&conn)
+        .await
+        .map_err(AppError::database)?;
+    if result.rows_affected == 0 {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "Product not found"));
+    }
+
+    Ok(Json("Product deleted"))
+}
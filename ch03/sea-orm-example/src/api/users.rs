@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Query, State, rejection::QueryRejection},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    SqlErr,
+    sea_query::{Expr, Func},
+};
+use serde::Deserialize;
+use serde_json::json;
+use username::UsernameError;
+
+use crate::entities::{
+    prelude::Users,
+    users::{ActiveModel, Column, Entity, Model},
+};
+
+/// 오류가 발생했을 때 반환하는 구조체
+pub struct AppError {
+    code: StatusCode,
+    message: String,
+}
+
+impl AppError {
+    pub fn new(code: StatusCode, message: impl Into<String>) -> Self {
+        AppError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// 원인을 알려줄 필요가 없는 데이터베이스 오류
+    pub fn database(_: DbErr) -> Self {
+        AppError::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        (self.code, Json(json!(self.message))).into_response()
+    }
+}
+
+/// 유저 이름 규칙에 맞지 않으면 400으로 바꾼다.
+impl From<UsernameError> for AppError {
+    fn from(err: UsernameError) -> Self {
+        AppError::new(StatusCode::BAD_REQUEST, err.to_string())
+    }
+}
+
+/// 유저 이름이 겹치면 409, 바꿀 유저가 없으면 404, 그 밖의 오류는 500으로 바꾼다.
+fn user_write_error(err: DbErr) -> AppError {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => {
+            AppError::new(StatusCode::CONFLICT, "Username already exists")
+        }
+        _ if matches!(err, DbErr::RecordNotUpdated) => {
+            AppError::new(StatusCode::NOT_FOUND, "User not found")
+        }
+        _ => AppError::database(err),
+    }
+}
+
+/// 조건을 묶는 방법
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Match {
+    /// 모든 조건을 만족하는 유저
+    #[default]
+    All,
+    /// 조건 중 하나라도 만족하는 유저
+    Any,
+}
+
+#[derive(Deserialize)]
+pub struct UserQuery {
+    id: Option<i32>,
+    username: Option<String>,
+    #[serde(default, rename = "match")]
+    match_: Match,
+}
+
+impl UserQuery {
+    /// 쿼리로 조건을 만든다. 조건이 하나도 없으면 모든 유저와 맞는다.
+    /// 유저 이름은 대소문자를 구분하지 않고 비교한다.
+    fn condition(self) -> Result<Condition, AppError> {
+        if self.id.is_none() && self.username.is_none() {
+            return Ok(Condition::all());
+        }
+
+        let username = self
+            .username
+            .as_deref()
+            .map(username::normalize)
+            .transpose()?;
+        let condition = match self.match_ {
+            Match::All => Condition::all(),
+            Match::Any => Condition::any(),
+        };
+        Ok(condition
+            .add_option(self.id.map(|id| Column::Id.eq(id)))
+            .add_option(username.map(|username| {
+                // `lower(username)` 인덱스를 쓰도록 같은 식으로 비교한다.
+                Expr::expr(Func::lower(Expr::col(Column::Username))).eq(username)
+            })))
+    }
+}
+
+/// 쿼리 문자열을 읽지 못하면 400으로 답한다.
+fn user_query(query: Result<Query<UserQuery>, QueryRejection>) -> Result<UserQuery, AppError> {
+    query
+        .map(|Query(query)| query)
+        .map_err(|rejection| AppError::new(StatusCode::BAD_REQUEST, rejection.body_text()))
+}
+
+/// "GET /users" 핸들러
+/// 조건에 맞는 유저 중 id가 가장 작은 유저를 반환한다. 맞는 유저가 없으면 404로 답한다.
+pub async fn get_user(
+    State(conn): State<DatabaseConnection>,
+    query: Result<Query<UserQuery>, QueryRejection>,
+) -> Result<Json<Model>, AppError> {
+    let condition = user_query(query)?.condition()?;
+
+    Entity::find()
+        .filter(condition)
+        .order_by_asc(Column::Id)
+        .one(&conn)
+        .await
+        .map_err(AppError::database)?
+        .map(Json)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))
+}
+
+/// "GET /users/list" 핸들러
+/// 조건에 맞는 유저를 id 순서로 모두 반환한다. 맞는 유저가 없으면 빈 목록을 반환한다.
+pub async fn list_users(
+    State(conn): State<DatabaseConnection>,
+    query: Result<Query<UserQuery>, QueryRejection>,
+) -> Result<Json<Vec<Model>>, AppError> {
+    let condition = user_query(query)?.condition()?;
+
+    Entity::find()
+        .filter(condition)
+        .order_by_asc(Column::Id)
+        .all(&conn)
+        .await
+        .map(Json)
+        .map_err(AppError::database)
+}
+
+#[derive(Deserialize)]
+pub struct UpsertModel {
+    id: Option<i32>,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+/// "POST /users" 핸들러
+pub async fn post_user(
+    State(conn): State<DatabaseConnection>,
+    Json(user): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    let (Some(username), Some(password)) = (user.username, user.password) else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "Username or Password not provided",
+        ));
+    };
+
+    ActiveModel {
+        username: Set(username::normalize(&username)?),
+        password: Set(password),
+        ..Default::default()
+    }
+    .insert(&conn)
+    .await
+    .map(Json)
+    .map_err(user_write_error)
+}
+
+/// "PUT /users" 핸들러
+pub async fn put_user(
+    State(conn): State<DatabaseConnection>,
+    Json(user): Json<UpsertModel>,
+) -> Result<Json<Model>, AppError> {
+    let Some(id) = user.id else {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "ID must be provided",
+        ));
+    };
+
+    if user.password.is_none() && user.username.is_none() {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "Username or Password must be provided",
+        ));
+    }
+
+    let username = user
+        .username
+        .as_deref()
+        .map(username::normalize)
+        .transpose()?;
+
+    // 보낸 필드만 Set으로 두면 그 필드만 UPDATE 문에 들어간다.
+    let user = ActiveModel {
+        id: Unchanged(id),
+        username: username.map_or(NotSet, Set),
+        password: user.password.map_or(NotSet, Set),
+    };
+
+    user.update(&conn).await.map(Json).map_err(user_write_error)
+}
+
+/// "DELETE /users" 핸들러
+pub async fn delete_user(
+    State(conn): State<DatabaseConnection>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<&'static str>, AppError> {
+    let id_param = params.get("id").ok_or(AppError::new(
+        StatusCode::BAD_REQUEST,
+        "User Id not provided",
+    ))?;
+    let id = id_param
+        .parse::<i32>()
+        .map_err(|_| AppError::new(StatusCode::BAD_REQUEST, "User Id must be an integer"))?;
+
+    let result = Users::delete_by_id(id)
+        .exec(&conn)
+        .await
+        .map_err(AppError::database)?;
+    if result.rows_affected == 0 {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "User not found"));
+    }
+
+    Ok(Json("User deleted"))
+}
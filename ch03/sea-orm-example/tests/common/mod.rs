@@ -0,0 +1,86 @@
+//! 통합 테스트 공용 도구
+//!
+//! 테스트마다 로컬 Postgres(DATABASE_URL)에 새 데이터베이스를 만들고 모든 마이그레이션을 적용한 뒤
+//! `sea_orm_example::router`로 서버와 같은 라우터를 만들어서 `oneshot`으로 요청을 보낸다.
+//! `TestApp`을 버릴 때 데이터베이스를 지우므로 테스트가 패닉해도 남지 않는다.
+
+// 테스트 파일마다 따로 컴파일되므로 쓰지 않는 도우미가 생긴다.
+#![allow(dead_code)]
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use http_body_util::BodyExt;
+use migration::testing::TestDatabase;
+use sea_orm::{Database, DatabaseConnection};
+use serde_json::Value;
+use tower::ServiceExt;
+
+pub struct TestApp {
+    pub router: Router,
+    pub conn: DatabaseConnection,
+    /// 버릴 때 데이터베이스를 지운다.
+    database: TestDatabase,
+}
+
+impl TestApp {
+    /// 새 데이터베이스에 마이그레이션을 적용하고 라우터를 만든다.
+    pub async fn spawn() -> Self {
+        let database = TestDatabase::migrated("sea_orm_test").await;
+        let conn = Database::connect(database.url()).await.unwrap();
+        let router = sea_orm_example::router(conn.clone());
+
+        TestApp {
+            router,
+            conn,
+            database,
+        }
+    }
+
+    /// 요청을 보내고 상태 코드와 JSON 본문을 반환한다. 본문이 비어 있으면 `Value::Null`이다.
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+        };
+
+        (status, body)
+    }
+
+    pub async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        self.request(Method::GET, uri, None).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::POST, uri, Some(body)).await
+    }
+
+    pub async fn put(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.request(Method::PUT, uri, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str) -> (StatusCode, Value) {
+        self.request(Method::DELETE, uri, None).await
+    }
+}
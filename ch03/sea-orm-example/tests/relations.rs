@@ -0,0 +1,121 @@
+//! 관계를 함께 가져오는 `include` 쿼리 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+/// 카테고리 두 개와 상품 세 개를 만들고 상품을 반환한다.
+async fn create_products(app: &TestApp) -> Vec<Value> {
+    for (name, parent) in [("Electronics", None), ("Laptop", Some("Electronics"))] {
+        let (status, _) = app
+            .post("/category", json!({ "name": name, "parent": parent }))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{name}");
+    }
+
+    let mut products = Vec::new();
+    for (title, price, category) in [
+        ("Radio", 100, "Electronics"),
+        ("MacBook", 2000, "Laptop"),
+        ("ThinkPad", 1500, "Laptop"),
+    ] {
+        let (status, product) = app
+            .post(
+                "/product",
+                json!({ "title": title, "price": price, "category": category }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{title}");
+        products.push(product);
+    }
+    products
+}
+
+#[tokio::test]
+async fn category_includes_its_products() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+
+    let (status, category) = app.get("/category/Laptop").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        category,
+        json!({ "name": "Laptop", "parent": "Electronics" })
+    );
+
+    let (status, category) = app.get("/category/Laptop?include=products").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        category,
+        json!({
+            "name": "Laptop",
+            "parent": "Electronics",
+            "products": [products[1], products[2]],
+        })
+    );
+
+    // 상품이 없는 카테고리도 빈 목록과 함께 반환한다.
+    app.post("/category", json!({ "name": "Phone", "parent": null }))
+        .await;
+    let (_, category) = app.get("/category/Phone?include=products").await;
+    assert_eq!(category["products"], json!([]));
+
+    let (status, _) = app.get("/category/Nope?include=products").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.get("/category/Nope").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.get("/category/Laptop?include=parent").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn product_includes_its_category() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+
+    let (status, found) = app.get("/product?category=Laptop&include=category").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        found,
+        json!([
+            {
+                "id": products[1]["id"],
+                "title": "MacBook",
+                "price": 2000,
+                "category": { "name": "Laptop", "parent": "Electronics" },
+            },
+            {
+                "id": products[2]["id"],
+                "title": "ThinkPad",
+                "price": 1500,
+                "category": { "name": "Laptop", "parent": "Electronics" },
+            },
+        ])
+    );
+
+    // include가 없으면 카테고리 이름만 반환한다.
+    let (_, found) = app.get("/product?title=Radio").await;
+    assert_eq!(found, json!([products[0]]));
+
+    let (status, _) = app.get("/product?include=images").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn delete_product_removes_product() {
+    let app = TestApp::spawn().await;
+    let products = create_products(&app).await;
+    let id = &products[0]["id"];
+
+    let (status, message) = app.delete(&format!("/product?id={id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(message, "Product deleted");
+
+    let (status, _) = app.delete(&format!("/product?id={id}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.delete("/product?id=abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
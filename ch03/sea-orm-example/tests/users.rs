@@ -0,0 +1,105 @@
+//! `/users` 핸들러 통합 테스트
+
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use common::TestApp;
+
+/// alice와 bob을 만들고 반환한다.
+async fn create_users(app: &TestApp) -> (Value, Value) {
+    let (status, alice) = app
+        .post("/users", json!({ "username": "Alice", "password": "pw" }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, bob) = app
+        .post("/users", json!({ "username": "bob", "password": "pw" }))
+        .await;
+    (alice, bob)
+}
+
+#[tokio::test]
+async fn get_user_matches_all_conditions_by_default() {
+    let app = TestApp::spawn().await;
+    let (alice, bob) = create_users(&app).await;
+    assert_eq!(alice["username"], "alice");
+
+    // /users는 맞는 유저 하나를, /users/list는 맞는 유저 모두를 반환한다.
+    let (status, user) = app.get("/users").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user, alice);
+    let (status, users) = app.get("/users/list").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users, json!([alice, bob]));
+
+    let (_, user) = app.get("/users?username=ALICE").await;
+    assert_eq!(user, alice);
+
+    let query = format!("id={}&username=bob", alice["id"]);
+    let (status, message) = app.get(&format!("/users?{query}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(message, "User not found");
+    let (status, users) = app.get(&format!("/users/list?{query}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users, json!([]));
+
+    let (_, users) = app.get(&format!("/users/list?{query}&match=any")).await;
+    assert_eq!(users, json!([alice, bob]));
+    let (_, user) = app.get(&format!("/users?{query}&match=any")).await;
+    assert_eq!(user, alice);
+}
+
+#[tokio::test]
+async fn invalid_queries_are_rejected_with_400() {
+    let app = TestApp::spawn().await;
+
+    for uri in [
+        "/users?id=abc",
+        "/users?match=some",
+        "/users?username=two%20words",
+        "/users/list?id=1.5",
+    ] {
+        let (status, _) = app.get(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+    }
+
+    let (status, _) = app.post("/users", json!({ "username": "bob" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.put("/users", json!({ "username": "bob" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.put("/users", json!({ "id": 1 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.delete("/users").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app.delete("/users?id=abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn missing_users_are_404_and_duplicates_409() {
+    let app = TestApp::spawn().await;
+    let (alice, _) = create_users(&app).await;
+
+    let (status, _) = app
+        .put("/users", json!({ "id": 9999, "password": "new" }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.delete("/users?id=9999").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .post("/users", json!({ "username": "BOB", "password": "pw" }))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = app
+        .put("/users", json!({ "id": alice["id"], "username": "Bob" }))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, message) = app.delete(&format!("/users?id={}", alice["id"])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(message, "User deleted");
+    let (status, _) = app.get(&format!("/users?id={}", alice["id"])).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
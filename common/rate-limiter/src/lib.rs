@@ -0,0 +1,294 @@
+//! 클라이언트별 요청 수 제한
+//!
+//! ch02/proxy_server와 ch03/module이 함께 쓴다. 어떤 경로에 어떤 제한을 걸지는 각 앱이 정한다.
+//!
+//! 클라이언트마다 토큰 버킷을 두고 요청마다 토큰을 하나씩 꺼낸다.
+//! 토큰은 `Quota::period`마다 하나씩 `Quota::burst`개까지 다시 찬다.
+//! 토큰이 없으면 429와 `Retry-After`로 답하고, 모든 응답에 `RateLimit-*` 헤더를 붙인다.
+//!
+//! 버킷은 `RateLimitStore`에 저장한다. 기본 저장소는 프로세스 메모리에 두는 `MemoryStore`이고,
+//! 여러 서버가 한도를 나눠 써야 하면 Redis 같은 저장소로 트레이트를 구현해서 바꿔 끼운다.
+
+use std::{
+    collections::HashMap,
+    env,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+const RATELIMIT_POLICY: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+/// 클라이언트 하나에 허용하는 요청 수
+#[derive(Clone, Copy, Debug)]
+pub struct Quota {
+    /// 한 번에 몰아서 보낼 수 있는 요청 수. 버킷의 크기다.
+    pub burst: u32,
+    /// 토큰 하나가 다시 차는 데 걸리는 시간
+    pub period: Duration,
+}
+
+impl Quota {
+    /// 1분에 `count`번. 1분 동안의 요청을 한 번에 보낼 수도 있다.
+    pub fn per_minute(count: u32) -> Self {
+        let count = count.max(1);
+        Quota {
+            burst: count,
+            period: Duration::from_secs(60) / count,
+        }
+    }
+
+    pub fn with_burst(self, burst: u32) -> Self {
+        Quota {
+            burst: burst.max(1),
+            ..self
+        }
+    }
+
+    /// 빈 버킷이 다시 가득 차는 데 걸리는 시간
+    fn window(&self) -> Duration {
+        self.period * self.burst
+    }
+}
+
+/// 토큰을 꺼낸 결과
+#[derive(Clone, Copy, Debug)]
+pub struct Decision {
+    pub allowed: bool,
+    /// 남은 토큰 수
+    pub remaining: u32,
+    /// 버킷이 가득 찰 때까지 남은 시간
+    pub reset: Duration,
+    /// 거절했으면 다음 토큰이 찰 때까지 남은 시간
+    pub retry_after: Duration,
+}
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// 토큰 버킷 저장소
+pub trait RateLimitStore: Send + Sync {
+    /// `key`의 버킷에서 토큰을 하나 꺼낸다.
+    fn take<'a>(&'a self, key: &'a str, quota: Quota) -> BoxFuture<'a, Decision>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// 프로세스 메모리에 버킷을 두는 저장소
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// 버킷이 이보다 많아지면 가득 찬 버킷을 지운다.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+impl MemoryStore {
+    fn take_now(&self, key: &str, quota: Quota, now: Instant) -> Decision {
+        let mut buckets = self.buckets.lock().unwrap();
+        let period = quota.period.as_secs_f64();
+        let burst = f64::from(quota.burst);
+
+        // 가득 찬 버킷은 새로 만든 버킷과 같으므로 지워도 된다.
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() / period < burst
+            });
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed / period).min(burst);
+        bucket.updated = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        let retry_after = if allowed {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) * period)
+        };
+
+        Decision {
+            allowed,
+            remaining: bucket.tokens as u32,
+            reset: Duration::from_secs_f64((burst - bucket.tokens) * period),
+            retry_after,
+        }
+    }
+}
+
+impl RateLimitStore for MemoryStore {
+    fn take<'a>(&'a self, key: &'a str, quota: Quota) -> BoxFuture<'a, Decision> {
+        let decision = self.take_now(key, quota, Instant::now());
+        Box::pin(async move { decision })
+    }
+}
+
+/// 요청을 보낸 클라이언트를 구분하는 방법
+#[derive(Clone, Copy, Debug)]
+pub enum KeyBy {
+    /// 클라이언트 IP 주소
+    Ip,
+    /// 인증 미들웨어가 넣은 `AuthenticatedUser`로 구분한다. 인증하지 않은 요청은 IP 주소로 구분한다.
+    /// 아직 이 확장을 넣는 앱이 없으므로 환경 변수로는 고를 수 없고 `RateLimiter::new`로만 쓴다.
+    User,
+}
+
+/// 인증 미들웨어가 확인한 사용자 id.
+/// 요청 확장에 넣어두면 `KeyBy::User`가 이 값으로 버킷을 나눈다.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser(pub String);
+
+impl FromStr for KeyBy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ip" => Ok(KeyBy::Ip),
+            "user" => Ok(KeyBy::User),
+            _ => Err(format!("Rate limit key must be ip or user: {value}")),
+        }
+    }
+}
+
+/// 경로 하나에 거는 요청 수 제한
+#[derive(Clone)]
+pub struct RateLimiter {
+    /// 같은 저장소를 쓰는 다른 경로와 버킷을 나누는 이름
+    name: &'static str,
+    quota: Quota,
+    key_by: KeyBy,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    /// `{prefix}_PER_MINUTE`, `{prefix}_BURST`, `{prefix}_KEY` 환경 변수에서 설정을 읽는다.
+    /// `{prefix}_PER_MINUTE`이 없거나 0이면 제한하지 않으므로 None을 반환한다.
+    /// `{prefix}_KEY=user`는 조용히 IP 주소로 제한하게 되므로 받지 않는다.
+    pub fn from_env(
+        name: &'static str,
+        prefix: &str,
+        store: Arc<dyn RateLimitStore>,
+    ) -> Option<Self> {
+        let parse = |key: String| {
+            env::var(&key).ok().map(|value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{key} must be a non-negative integer"))
+            })
+        };
+
+        let per_minute = parse(format!("{prefix}_PER_MINUTE")).filter(|&count| count > 0)?;
+        let burst = parse(format!("{prefix}_BURST")).unwrap_or(per_minute);
+        let key_by = env::var(format!("{prefix}_KEY"))
+            .map(|value| value.parse().unwrap_or_else(|err| panic!("{err}")))
+            .unwrap_or(KeyBy::Ip);
+        if let KeyBy::User = key_by {
+            panic!("{prefix}_KEY=user needs a middleware that inserts AuthenticatedUser");
+        }
+        tracing::info!("{name} 요청 수 제한: 1분에 {per_minute}번, 최대 {burst}번 연속");
+
+        Some(RateLimiter::new(
+            name,
+            Quota::per_minute(per_minute).with_burst(burst),
+            key_by,
+            store,
+        ))
+    }
+
+    pub fn new(
+        name: &'static str,
+        quota: Quota,
+        key_by: KeyBy,
+        store: Arc<dyn RateLimitStore>,
+    ) -> Self {
+        RateLimiter {
+            name,
+            quota,
+            key_by,
+            store,
+        }
+    }
+
+    fn client_key(&self, request: &Request) -> String {
+        // 검증하지 않은 헤더로 나누면 요청마다 값을 바꿔서 제한을 피할 수 있으므로 확인된 사용자만 쓴다.
+        // 키는 여러 서버가 저장소를 함께 써도 같도록 프로세스마다 달라지는 해시 없이 만든다.
+        if let KeyBy::User = self.key_by
+            && let Some(AuthenticatedUser(user)) = request.extensions().get()
+        {
+            return format!("{}:user:{user}", self.name);
+        }
+
+        // 서버를 `into_make_service_with_connect_info`로 띄웠을 때만 클라이언트 주소를 안다.
+        match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+            Some(ConnectInfo(addr)) => format!("{}:ip:{}", self.name, addr.ip()),
+            None => format!("{}:ip:unknown", self.name),
+        }
+    }
+
+    fn headers(&self, decision: &Decision) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RATELIMIT_LIMIT, self.quota.burst.into());
+        headers.insert(RATELIMIT_REMAINING, decision.remaining.into());
+        headers.insert(RATELIMIT_RESET, ceil_secs(decision.reset).into());
+        let policy = format!("{};w={}", self.quota.burst, ceil_secs(self.quota.window()));
+        headers.insert(RATELIMIT_POLICY, policy.parse().unwrap());
+        headers
+    }
+}
+
+/// 헤더에 넣을 초. 0.5초 남았을 때 0초라고 알려주지 않도록 올림한다.
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs_f64().ceil() as u64
+}
+
+/// 요청 수 제한 미들웨어.
+/// `middleware::from_fn_with_state(limiter, rate_limit)`로 경로마다 건다.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = limiter.client_key(&request);
+    let decision = limiter.store.take(&key, limiter.quota).await;
+    let headers = limiter.headers(&decision);
+
+    if !decision.allowed {
+        tracing::info!("{key} 요청 수 제한 초과");
+        let retry_after = ceil_secs(decision.retry_after).max(1);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            headers,
+            [(header::RETRY_AFTER, retry_after)],
+            Json(json!("Too many requests")),
+        )
+            .into_response();
+    }
+
+    let mut response = next.run(request).await;
+    response.headers_mut().extend(headers);
+    response
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use rate_limiter::{MemoryStore, RateLimiter};
+
+// 환경 변수는 프로세스 전체에서 함께 쓰므로 테스트마다 다른 접두사를 쓴다.
+
+#[test]
+fn missing_or_zero_per_minute_disables_the_limit() {
+    let store = Arc::new(MemoryStore::default());
+    assert!(RateLimiter::from_env("missing", "TEST_MISSING_RATE_LIMIT", store.clone()).is_none());
+
+    unsafe { std::env::set_var("TEST_ZERO_RATE_LIMIT_PER_MINUTE", "0") };
+    assert!(RateLimiter::from_env("zero", "TEST_ZERO_RATE_LIMIT", store).is_none());
+}
+
+#[test]
+fn ip_key_is_accepted() {
+    unsafe {
+        std::env::set_var("TEST_IP_RATE_LIMIT_PER_MINUTE", "10");
+        std::env::set_var("TEST_IP_RATE_LIMIT_KEY", "ip");
+    }
+    let store = Arc::new(MemoryStore::default());
+    assert!(RateLimiter::from_env("ip", "TEST_IP_RATE_LIMIT", store).is_some());
+}
+
+#[test]
+#[should_panic(expected = "TEST_USER_RATE_LIMIT_KEY=user needs a middleware")]
+fn user_key_is_rejected_without_authentication() {
+    unsafe {
+        std::env::set_var("TEST_USER_RATE_LIMIT_PER_MINUTE", "10");
+        std::env::set_var("TEST_USER_RATE_LIMIT_KEY", "user");
+    }
+    let store = Arc::new(MemoryStore::default());
+    RateLimiter::from_env("user", "TEST_USER_RATE_LIMIT", store);
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use rate_limiter::{MemoryStore, Quota, RateLimitStore};
+
+#[tokio::test]
+async fn bucket_is_emptied_by_burst() {
+    let store = MemoryStore::default();
+    let quota = Quota::per_minute(60).with_burst(2);
+
+    let first = store.take("a", quota).await;
+    assert!(first.allowed);
+    assert_eq!(first.remaining, 1);
+    assert!(store.take("a", quota).await.allowed);
+
+    let rejected = store.take("a", quota).await;
+    assert!(!rejected.allowed);
+    assert_eq!(rejected.remaining, 0);
+    assert!(rejected.retry_after > Duration::ZERO && rejected.retry_after <= quota.period);
+}
+
+#[tokio::test]
+async fn keys_have_separate_buckets() {
+    let store = MemoryStore::default();
+    let quota = Quota::per_minute(1);
+
+    assert!(store.take("a", quota).await.allowed);
+    assert!(!store.take("a", quota).await.allowed);
+    assert!(store.take("b", quota).await.allowed);
+}
@@ -0,0 +1,52 @@
+//! 유저 이름 규칙
+//!
+//! `module`과 `sea-orm-example`의 API, `migration`의 `seed` 명령이 모두 이 규칙으로 이름을 저장하고 비교한다.
+//! `idx_users_username_lower` 인덱스가 이 규칙으로 바꾼 이름이 겹치지 않게 막는다.
+
+use std::fmt;
+
+/// 유저 이름의 최대 글자 수
+pub const MAX_LEN: usize = 32;
+
+/// 규칙에 맞지 않는 유저 이름
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsernameError {
+    Empty,
+    TooLong,
+    Whitespace,
+    Control,
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsernameError::Empty => write!(f, "Username must not be empty"),
+            UsernameError::TooLong => write!(f, "Username must be at most {MAX_LEN} characters"),
+            UsernameError::Whitespace => write!(f, "Username must not contain whitespace"),
+            UsernameError::Control => write!(f, "Username must not contain control characters"),
+        }
+    }
+}
+
+impl std::error::Error for UsernameError {}
+
+/// 유저 이름을 저장하고 비교하는 형태로 바꾼다.
+/// 앞뒤 공백을 지우고 소문자로 바꾼다. 비어 있거나 너무 길거나 공백이나 제어 문자가 있으면 오류를 반환한다.
+pub fn normalize(username: &str) -> Result<String, UsernameError> {
+    let username = username.trim().to_lowercase();
+
+    if username.is_empty() {
+        return Err(UsernameError::Empty);
+    }
+    if username.chars().count() > MAX_LEN {
+        return Err(UsernameError::TooLong);
+    }
+    if username.chars().any(char::is_control) {
+        return Err(UsernameError::Control);
+    }
+    if username.chars().any(char::is_whitespace) {
+        return Err(UsernameError::Whitespace);
+    }
+
+    Ok(username)
+}
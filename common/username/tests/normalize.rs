@@ -0,0 +1,25 @@
+use username::{MAX_LEN, UsernameError, normalize};
+
+#[test]
+fn trims_and_lowercases() {
+    assert_eq!(normalize("  Alice ").unwrap(), "alice");
+}
+
+#[test]
+fn rejects_invalid_usernames() {
+    assert_eq!(normalize("   "), Err(UsernameError::Empty));
+    assert_eq!(
+        normalize(&"a".repeat(MAX_LEN + 1)),
+        Err(UsernameError::TooLong)
+    );
+    assert_eq!(normalize("al ice"), Err(UsernameError::Whitespace));
+    assert_eq!(normalize("al\u{0}ice"), Err(UsernameError::Control));
+}
+
+#[test]
+fn control_characters_have_their_own_message() {
+    assert_eq!(
+        UsernameError::Control.to_string(),
+        "Username must not contain control characters"
+    );
+}